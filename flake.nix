@@ -20,6 +20,7 @@
             bacon
             trunk
             nixpkgs-fmt
+            python3
           ];
           buildInputs = with pkgs; lib.optionals stdenv.isDarwin [ libiconv ];
         }
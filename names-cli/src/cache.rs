@@ -0,0 +1,113 @@
+//! On-disk cache of API results, so no name is ever looked up twice, and a run
+//! cut short by the daily quota can be resumed just by running it again.
+//!
+//! The cache is a JSON lines file, appended to after every batch, so even an
+//! interrupted run loses nothing. Any partly-written last line is skipped.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use anyhow::Context;
+use names_core::api::{CountryResult, GenderResult, Service};
+use names_core::Name;
+use serde::{Deserialize, Serialize};
+
+/// API results read from, and written to, the cache file (if any)
+#[derive(Default)]
+pub struct Cache {
+    file: Option<(PathBuf, File)>,
+    gender: HashMap<Name, GenderResult>,
+    country: HashMap<Name, Vec<CountryResult>>,
+}
+
+/// One line of the cache file
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "service", rename_all = "lowercase")]
+pub enum Entry {
+    Gender { name: Name, result: GenderResult },
+    Country { name: Name, result: Vec<CountryResult> },
+}
+
+/// Default cache file location, in the user's cache directory
+pub fn default_path() -> anyhow::Result<PathBuf> {
+    let dir =
+        dirs::cache_dir().context("no cache directory - use --cache or --no-cache")?;
+    Ok(dir.join("names").join("cache.jsonl"))
+}
+
+impl Cache {
+    /// Cache that only lasts for this run
+    pub fn disabled() -> Self { Cache::default() }
+
+    /// Load the cache file, creating it if necessary.
+    pub fn open(path: PathBuf) -> anyhow::Result<Self> {
+        let mut cache = Cache::default();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("creating {}", dir.display()))?;
+        }
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            for entry in text.lines().filter_map(|line| serde_json::from_str(line).ok()) {
+                cache.add(entry);
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("opening {}", path.display()))?;
+        cache.file = Some((path, file));
+        Ok(cache)
+    }
+
+    /// Whether results are saved for next time
+    pub fn persistent(&self) -> bool { self.file.is_some() }
+
+    /// Cached gender result for a name
+    pub fn gender(&self, name: &Name) -> Option<&GenderResult> { self.gender.get(name) }
+
+    /// Cached country results for a name
+    pub fn country(&self, name: &Name) -> Option<&[CountryResult]> {
+        self.country.get(name).map(Vec::as_slice)
+    }
+
+    /// Whether we already have a name's result for one service
+    pub fn contains(&self, service: Service, name: &Name) -> bool {
+        match service {
+            Service::Gender => self.gender.contains_key(name),
+            Service::Country => self.country.contains_key(name),
+        }
+    }
+
+    /// Add newly fetched results, saving them straight away.
+    pub fn insert(&mut self, entries: Vec<Entry>) -> anyhow::Result<()> {
+        if let Some((path, file)) = &self.file {
+            let mut writer = BufWriter::new(file);
+            for entry in &entries {
+                serde_json::to_writer(&mut writer, entry)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush().with_context(|| format!("writing {}", path.display()))?;
+        }
+        for entry in entries {
+            self.add(entry);
+        }
+        Ok(())
+    }
+
+    /// Internal helper function: add one entry in memory.
+    fn add(&mut self, entry: Entry) {
+        match entry {
+            Entry::Gender { name, result } => {
+                self.gender.insert(name, result);
+            }
+            Entry::Country { name, result } => {
+                self.country.insert(name, result);
+            }
+        }
+    }
+}
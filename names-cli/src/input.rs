@@ -0,0 +1,62 @@
+//! Read the names to look up, either one per line, or from a CSV column.
+
+use anyhow::{bail, Context};
+use names_core::normalise::{parse_line, split_lines};
+use names_core::Name;
+
+/// One input name, as given, and as normalised for querying
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub input: String,
+    pub name: Name,
+}
+
+/// Take each line as a name, just like the web app's text box.
+pub fn lines(text: &str) -> Vec<Row> { split_lines(text).filter_map(row).collect() }
+
+/// Take the names from one column of CSV data, identified by header name, or
+/// 1-based column number.
+pub fn csv_column(
+    text: &str,
+    column: &str,
+    has_header: bool,
+    delimiter: char,
+) -> anyhow::Result<Vec<Row>> {
+    if !delimiter.is_ascii() {
+        bail!("the delimiter must be a single ASCII character");
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    // Prefer a matching header, so a column called "2" still works.
+    let by_header = match has_header {
+        true => reader
+            .headers()
+            .context("reading CSV header")?
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(column.trim())),
+        false => None,
+    };
+    let index = match (by_header, column.trim().parse::<usize>()) {
+        (Some(index), _) => index,
+        (None, Ok(number)) if number > 0 => number - 1,
+        _ => bail!("no such column {:?}", column),
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.context("reading CSV")?;
+        rows.extend(record.get(index).and_then(row));
+    }
+    Ok(rows)
+}
+
+/// Internal helper function: the [Row] for one input field, if it isn't to be
+/// ignored.
+fn row(input: &str) -> Option<Row> {
+    let name = parse_line(input)?;
+    Some(Row { input: input.trim().to_owned(), name })
+}
@@ -0,0 +1,132 @@
+//! Drive the [Queue] from names-core, one request at a time, filling the
+//! [Cache] with the results.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use futures::executor::block_on;
+use names_core::api::{get_countries, get_genders, ApiConfig, ApiError, Service};
+use names_core::native::UreqClient;
+use names_core::queue::{Outcome, Queue};
+use names_core::Name;
+
+use crate::cache::{Cache, Entry};
+use crate::input::Row;
+
+/// Why a run finished without looking up everything
+#[derive(Debug)]
+pub struct Stopped {
+    /// Number of lookups not done because the daily API limit ran out
+    pub quota: usize,
+    /// Number of lookups that failed for other reasons (already reported)
+    pub failed: usize,
+    /// Whether the results so far are cached, so the run can be resumed
+    pub cached: bool,
+}
+
+/// Look up every name not already cached, returning what was left undone, if
+/// anything.
+pub fn run(
+    rows: &[Row],
+    cache: &mut Cache,
+    client: &UreqClient,
+    config: &ApiConfig,
+) -> anyhow::Result<Option<Stopped>> {
+    let mut queue = Queue::<()>::default();
+    let mut seen = HashSet::new();
+    for row in rows.iter().filter(|row| seen.insert(&row.name)) {
+        for service in Service::ALL {
+            if !cache.contains(service, &row.name) {
+                queue.enqueue(service, row.name.clone());
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let now = || start.elapsed().as_secs_f64() * 1000.0;
+    let mut stopped = Stopped { quota: 0, failed: 0, cached: cache.persistent() };
+    for service in Service::ALL {
+        let total = queue.scheduler(service).queued();
+        if total > 0 {
+            eprintln!("{}: looking up {} names", service.domain(), total);
+        }
+
+        // Each batch is complete before we ask for the next, so the queue only
+        // ever has one in flight.
+        while let Some(batch) = queue.start_batch(service, now(), || ()) {
+            let result = block_on(fetch(service, client, config, &batch.names));
+            let (entries, error) = match result {
+                Ok(entries) => (entries, None),
+                Err(err) => (Vec::new(), Some(err)),
+            };
+            match (queue.finished(service, batch, error.as_ref(), now(), true), error) {
+                (Outcome::Done, _) => cache.insert(entries)?,
+                (Outcome::BackOff(delay), _) => {
+                    sleep(Duration::from_millis(delay.into()));
+                    queue.end_backoff(service);
+                }
+                (Outcome::Failed(names), Some(ApiError::LimitExceeded)) => {
+                    stopped.quota += names.len();
+                }
+                (Outcome::Failed(names), Some(err)) => {
+                    eprintln!("{}: {} ({})", service.domain(), err, names.join(", "));
+                    stopped.failed += names.len();
+                }
+                _ => {}
+            }
+        }
+    }
+    if stopped.quota > 0 || stopped.failed > 0 {
+        Ok(Some(stopped))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Internal helper function: fetch one batch, as cache entries.
+async fn fetch(
+    service: Service,
+    client: &UreqClient,
+    config: &ApiConfig,
+    names: &[Name],
+) -> Result<Vec<Entry>, ApiError> {
+    Ok(match service {
+        Service::Gender => get_genders(client, config, names)
+            .await?
+            .into_iter()
+            .map(|(name, result)| Entry::Gender { name, result })
+            .collect(),
+        Service::Country => get_countries(client, config, names)
+            .await?
+            .into_iter()
+            .map(|(name, result)| Entry::Country { name, result })
+            .collect(),
+    })
+}
+
+impl Stopped {
+    /// Whether the run can be finished later, by just running it again
+    pub fn resumable(&self) -> bool { self.quota > 0 }
+}
+
+impl fmt::Display for Stopped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sentences = Vec::new();
+        if self.failed > 0 {
+            sentences.push(format!("{} lookups failed.", self.failed));
+        }
+        if self.quota > 0 {
+            sentences.push(format!(
+                "The daily API limit ran out with {} lookups still to do.",
+                self.quota
+            ));
+            sentences.push(match self.cached {
+                true => "Run the same command again tomorrow to resume.".to_owned(),
+                false => "Without the cache, the next run starts afresh.".to_owned(),
+            });
+        }
+        write!(f, "{}", sentences.join(" "))
+    }
+}
@@ -0,0 +1,170 @@
+//! Command-line tool for batch lookups against genderize.io and nationalize.io
+#![warn(rust_2018_idioms, missing_docs, unused)]
+
+mod cache;
+mod input;
+mod lookup;
+mod offline;
+mod output;
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use names_core::api::ApiConfig;
+use names_core::native::UreqClient;
+
+use crate::cache::Cache;
+use crate::lookup::Stopped;
+use crate::offline::OfflineTableArgs;
+
+/// Exit code when the daily API limit cut the run short (rather than 1 for any
+/// other failure)
+const EXIT_QUOTA: u8 = 2;
+
+/// Guess gender and country from first names, using genderize.io and
+/// nationalize.io.
+///
+/// Results are cached on disk, so running the same command again after the
+/// daily quota runs out picks up where it left off.
+#[derive(Parser, Debug)]
+#[command(name = "names", version, args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Input file (default: standard input)
+    input: Option<PathBuf>,
+
+    /// Read names from this CSV column (header name, or 1-based number),
+    /// rather than taking each line as a name
+    #[arg(short, long)]
+    column: Option<String>,
+
+    /// The CSV input has no header row (so any --column must be a number)
+    #[arg(long, requires = "column")]
+    no_header: bool,
+
+    /// CSV input field delimiter
+    #[arg(short, long, default_value_t = ',')]
+    delimiter: char,
+
+    /// Output file (default: standard output)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+
+    /// API key, for a paid plan with a higher quota
+    #[arg(long, env = "NAMES_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Base URL of a caching proxy (eg names-server) to go via, rather than
+    /// the APIs themselves
+    #[arg(long, env = "NAMES_API_URL")]
+    api_url: Option<String>,
+
+    /// Number of seconds to wait for each API request
+    #[arg(long, default_value_t = 30)]
+    timeout: u32,
+
+    /// Cache file (default: names/cache.jsonl in the user's cache directory)
+    #[arg(long, conflicts_with = "no_cache")]
+    cache: Option<PathBuf>,
+
+    /// Don't read or write the cache (so a run cut short can't be resumed)
+    #[arg(long)]
+    no_cache: bool,
+}
+
+/// Other things to do, rather than looking names up
+#[derive(Subcommand, Debug)]
+enum Command {
+    OfflineTable(OfflineTableArgs),
+}
+
+/// Output formats
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One row per input name, with the countries in a single field
+    Csv,
+    /// An array of objects, one per input name
+    Json,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if let Some(Command::OfflineTable(args)) = &args.command {
+        return match offline::run(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Error: {err:#}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    match run(args) {
+        Ok(None) => ExitCode::SUCCESS,
+        Ok(Some(stopped)) => {
+            eprintln!("{stopped}");
+            match stopped.resumable() {
+                true => ExitCode::from(EXIT_QUOTA),
+                false => ExitCode::FAILURE,
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Look up every name in the input, and write out the results, returning
+/// anything left undone.
+fn run(args: Args) -> anyhow::Result<Option<Stopped>> {
+    // Read the names
+    let mut text = String::new();
+    match &args.input {
+        Some(path) => BufReader::new(
+            File::open(path).with_context(|| format!("opening {}", path.display()))?,
+        )
+        .read_to_string(&mut text),
+        None => io::stdin().read_to_string(&mut text),
+    }
+    .context("reading input")?;
+    let rows = match &args.column {
+        Some(column) => {
+            input::csv_column(&text, column, !args.no_header, args.delimiter)?
+        }
+        None => input::lines(&text),
+    };
+
+    // Look them up, going via the cache
+    let mut cache = match (args.no_cache, &args.cache) {
+        (true, _) => Cache::disabled(),
+        (false, Some(path)) => Cache::open(path.clone())?,
+        (false, None) => Cache::open(cache::default_path()?)?,
+    };
+    let config = ApiConfig {
+        api_key: args.api_key.filter(|key| !key.trim().is_empty()),
+        base_url: args.api_url,
+    };
+    let client = UreqClient::new(args.timeout);
+    let stopped = lookup::run(&rows, &mut cache, &client, &config)?;
+
+    // Write out the results
+    let writer: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(
+            File::create(path).with_context(|| format!("creating {}", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    output::write(BufWriter::new(writer), args.format, &rows, &cache)
+        .context("writing output")?;
+    Ok(stopped)
+}
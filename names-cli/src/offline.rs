@@ -0,0 +1,146 @@
+//! Build the table of common first names that the app falls back on for offline
+//! gender estimates (see [names_core::offline]), from the US Social Security
+//! Administration's national baby name statistics (public domain,
+//! https://www.ssa.gov/oact/babynames/).
+//!
+//! The statistics come as `names.zip`, holding one `yobYYYY.txt` file per year
+//! of births, with lines like `Mary,F,7065`.
+
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use clap::Args;
+use names_core::offline::Table;
+use zip::ZipArchive;
+
+/// Where the statistics are published
+const SOURCE: &str = "https://www.ssa.gov/oact/babynames/names.zip";
+
+/// Build the app's table of common first names, for offline gender estimates
+#[derive(Args, Debug)]
+pub struct OfflineTableArgs {
+    /// URL or path of the statistics' names.zip, or a directory of its
+    /// yobYYYY.txt files
+    #[arg(long, default_value = SOURCE)]
+    source: String,
+
+    /// Only count births from this year on
+    #[arg(long, default_value_t = 1940)]
+    since: u32,
+
+    /// Number of names to keep
+    #[arg(long, default_value_t = 20_000)]
+    limit: usize,
+
+    /// Table to write, where the app fetches it from
+    #[arg(short, long, default_value = "static/offline/names.bin")]
+    output: PathBuf,
+}
+
+/// Build the table, and write it out.
+pub fn run(args: &OfflineTableArgs) -> anyhow::Result<()> {
+    let mut counts = Vec::new();
+    let mut years = 0;
+    for (year, text) in year_files(&args.source)? {
+        if year < args.since {
+            continue;
+        }
+        years += 1;
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            counts.push(parse(line).with_context(|| format!("bad line {line:?}"))?);
+        }
+    }
+    if years == 0 {
+        bail!("no yobYYYY.txt files from {} on in {}", args.since, args.source);
+    }
+
+    let table = Table::from_counts(counts, args.limit);
+    if let Some(dir) = args.output.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    fs::write(&args.output, table.encode())
+        .with_context(|| format!("writing {}", args.output.display()))?;
+    eprintln!(
+        "Wrote {} names from {years} years to {}",
+        table.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+/// Internal helper function: the year and contents of each yobYYYY.txt file in
+/// the source.
+fn year_files(source: &str) -> anyhow::Result<Vec<(u32, String)>> {
+    let path = Path::new(source);
+    if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path).with_context(|| format!("reading {source}"))? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|name| name.to_str());
+            if let Some(year) = name.and_then(year) {
+                let text = fs::read_to_string(&path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                files.push((year, text));
+            }
+        }
+        return Ok(files);
+    }
+    if path.is_file() {
+        let file = File::open(path).with_context(|| format!("opening {source}"))?;
+        return unzip(file).with_context(|| format!("reading {source}"));
+    }
+    if !source.starts_with("http://") && !source.starts_with("https://") {
+        bail!("{source} not found");
+    }
+    eprintln!("Downloading {source}");
+    let mut data = Vec::new();
+    ureq::get(source)
+        .call()
+        .with_context(|| format!("downloading {source}"))?
+        .into_reader()
+        .read_to_end(&mut data)
+        .with_context(|| format!("downloading {source}"))?;
+    unzip(Cursor::new(data)).with_context(|| format!("reading {source}"))
+}
+
+/// Internal helper function: the yobYYYY.txt files in a zip archive.
+fn unzip(reader: impl Read + Seek) -> anyhow::Result<Vec<(u32, String)>> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut files = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if let Some(year) = year(file.name()) {
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            files.push((year, text));
+        }
+    }
+    Ok(files)
+}
+
+/// Internal helper function: the year of births a file is for, if it's one of
+/// the yobYYYY.txt files.
+fn year(file_name: &str) -> Option<u32> {
+    let year = file_name.rsplit('/').next()?.strip_prefix("yob")?.strip_suffix(".txt")?;
+    match year.len() {
+        4 => year.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Internal helper function: parse one line of a yobYYYY.txt file into a name,
+/// and numbers of females and males.
+fn parse(line: &str) -> anyhow::Result<(String, u32, u32)> {
+    let mut fields = line.trim().split(',');
+    let (name, sex, count) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(name), Some(sex), Some(count)) => (name, sex, count.parse::<u32>()?),
+        _ => bail!("expected name,sex,count"),
+    };
+    match sex {
+        "F" => Ok((name.to_owned(), count, 0)),
+        "M" => Ok((name.to_owned(), 0, count)),
+        _ => bail!("unknown sex {sex:?}"),
+    }
+}
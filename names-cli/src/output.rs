@@ -0,0 +1,86 @@
+//! Write out the results, in one of the supported [Format]s.
+
+use std::io::Write;
+
+use names_core::api::{CountryResult, Gender};
+use serde::Serialize;
+
+use crate::cache::Cache;
+use crate::input::Row;
+use crate::Format;
+
+/// CSV column headers
+const CSV_HEADERS: [&str; 6] =
+    ["input", "name", "gender", "probability", "count", "countries"];
+
+/// One output record. Everything from the APIs is [None] if that lookup wasn't
+/// done, or failed.
+#[derive(Serialize, Debug)]
+struct Record<'a> {
+    input: &'a str,
+    name: &'a str,
+    gender: Option<Gender>,
+    probability: Option<f32>,
+    count: Option<u32>,
+    countries: Option<&'a [CountryResult]>,
+}
+
+/// Write out a record for every input row, in the same order.
+pub fn write(
+    mut out: impl Write,
+    format: Format,
+    rows: &[Row],
+    cache: &Cache,
+) -> anyhow::Result<()> {
+    let records = rows.iter().map(|row| {
+        let gender = cache.gender(&row.name);
+        Record {
+            input: &row.input,
+            name: &row.name,
+            gender: gender.and_then(|g| g.gender),
+            probability: gender.map(|g| g.probability),
+            count: gender.map(|g| g.count),
+            countries: cache.country(&row.name),
+        }
+    });
+
+    match format {
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(CSV_HEADERS)?;
+            for record in records {
+                writer.write_record([
+                    record.input.to_owned(),
+                    record.name.to_owned(),
+                    record.gender.map(Gender::key).unwrap_or_default().to_owned(),
+                    fmt_option(record.probability),
+                    fmt_option(record.count),
+                    record.countries.map(fmt_countries).unwrap_or_default(),
+                ])?;
+            }
+            writer.flush()?;
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, &records.collect::<Vec<_>>())?;
+            writeln!(out)?;
+            out.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Internal helper function: format an optional value for CSV, leaving it
+/// empty if missing.
+fn fmt_option(value: Option<impl ToString>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Internal helper function: format country results for a single CSV field,
+/// eg "FR:0.45 BE:0.12".
+fn fmt_countries(countries: &[CountryResult]) -> String {
+    countries
+        .iter()
+        .map(|c| format!("{}:{}", c.country, c.probability))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
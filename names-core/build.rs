@@ -0,0 +1,152 @@
+//! Generate the country lookup tables from data/iso3166.tsv, and the nickname
+//! table from data/nicknames.tsv, so those files are the single source of
+//! truth, rather than hand-maintained `phf_map!`s.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{env, fs};
+
+/// Source data file
+const DATA: &str = "data/iso3166.tsv";
+
+/// Source data file for nicknames
+const NICKNAMES: &str = "data/nicknames.tsv";
+
+/// One row of the data file
+struct Row<'a> {
+    alpha2: &'a str,
+    alpha3: &'a str,
+    numeric: &'a str,
+    sub_region: &'a str,
+    continent: &'a str,
+    names: [&'a str; 3],
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={DATA}");
+    let data = fs::read_to_string(DATA).expect("reading country data");
+    let rows: Vec<Row<'_>> = data
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| {
+            parse(line).unwrap_or_else(|| panic!("{DATA}:{}: bad row", n + 1))
+        })
+        .collect();
+
+    // Main table of everything we know about each country
+    let mut map = phf_codegen::Map::new();
+    for row in &rows {
+        let numeric = match row.numeric {
+            "" => String::from("None"),
+            n => format!("Some({})", n.parse::<u16>().expect("numeric code")),
+        };
+        let sub_region = match row.sub_region {
+            "" => String::from("None"),
+            s => format!("Some(SubRegion::{})", variant(s)),
+        };
+        map.entry(
+            row.alpha2,
+            &format!(
+                "Country::new({:?}, {:?}, {numeric}, {:?}, {sub_region}, Continent::{})",
+                row.alpha2,
+                row.alpha3,
+                row.names[0],
+                variant(row.continent)
+            ),
+        );
+    }
+    write("iso3166.rs", "COUNTRY", "Country", &map);
+
+    // Per-locale name tables
+    for (i, locale) in ["fr", "de"].into_iter().enumerate() {
+        let mut map = phf_codegen::Map::new();
+        for row in &rows {
+            map.entry(row.alpha2, &format!("{:?}", row.names[i + 1]));
+        }
+        let name = format!("COUNTRY_{}", locale.to_uppercase());
+        write(&format!("country_{locale}.rs"), &name, "&'static str", &map);
+    }
+
+    nicknames();
+}
+
+/// Generate the table of nicknames, by lower-case nickname, checking each maps
+/// to just one full form, and that full forms aren't nicknames themselves.
+fn nicknames() {
+    println!("cargo:rerun-if-changed={NICKNAMES}");
+    let data = fs::read_to_string(NICKNAMES).expect("reading nickname data");
+    let mut full_forms = HashSet::new();
+    let mut nicknames: BTreeMap<String, &str> = BTreeMap::new();
+    for (n, line) in data.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at = format!("{NICKNAMES}:{}", n + 1);
+        let (full, nicks) =
+            line.split_once('\t').unwrap_or_else(|| panic!("{at}: bad row"));
+        full_forms.insert(full.to_ascii_lowercase());
+        for nick in nicks.split(',').map(str::trim) {
+            if nick.is_empty() || !nick.bytes().all(|b| b.is_ascii_alphabetic()) {
+                panic!("{at}: bad nickname {nick:?}");
+            }
+            if let Some(other) = nicknames.insert(nick.to_ascii_lowercase(), full) {
+                panic!("{at}: {nick} is already a nickname for {other}");
+            }
+        }
+    }
+    let mut map = phf_codegen::Map::new();
+    for (nick, full) in &nicknames {
+        if full_forms.contains(nick) {
+            panic!("{NICKNAMES}: {nick} is both a nickname and a full form");
+        }
+        map.entry(nick.as_str(), &format!("{full:?}"));
+    }
+    write("nicknames.rs", "NICKNAMES", "&'static str", &map);
+}
+
+/// Parse one tab-separated row.
+fn parse(line: &str) -> Option<Row<'_>> {
+    let mut cols = line.split('\t');
+    let mut next = || cols.next();
+    let row = Row {
+        alpha2: next()?,
+        alpha3: next()?,
+        numeric: next()?,
+        sub_region: next()?,
+        continent: next()?,
+        names: [next()?, next()?, next()?],
+    };
+    if row.alpha2.len() == 2 && cols.next().is_none() {
+        Some(row)
+    } else {
+        None
+    }
+}
+
+/// Enum variant for a region name, eg "South-eastern Asia" to
+/// "SouthEasternAsia".
+fn variant(name: &str) -> String {
+    let mut variant = String::new();
+    for word in name.split([' ', '-']) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            variant.extend(first.to_uppercase().chain(chars));
+        }
+    }
+    variant
+}
+
+/// Write out a generated table as a `const`.
+fn write(file: &str, name: &str, value_type: &str, map: &phf_codegen::Map<&str>) {
+    let mut code = String::new();
+    writeln!(
+        code,
+        "const {name}: phf::Map<&'static str, {value_type}> = {};",
+        map.build()
+    )
+    .unwrap();
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join(file);
+    fs::write(path, code).expect("writing generated table");
+}
@@ -0,0 +1,74 @@
+#!/usr/bin/env python3
+"""Regenerate the codes and regions in data/iso3166.tsv from the ISO 3166 / UN
+M49 compilation at https://github.com/lukes/ISO-3166-Countries-with-Regional-Codes
+
+Names and continents already in the file are kept, since they have been
+shortened and translated by hand. Any newly assigned country gets placeholder
+names and a guessed continent, and is reported so these can be checked. The
+section of codes outside the official list is left untouched.
+"""
+
+import csv
+import io
+import sys
+import urllib.request
+from pathlib import Path
+
+SOURCE = ("https://raw.githubusercontent.com/lukes/"
+          "ISO-3166-Countries-with-Regional-Codes/master/all/all.csv")
+DATA = Path(__file__).parent / "iso3166.tsv"
+OFFICIAL = "# Officially assigned codes\n"
+UNOFFICIAL = "# Codes outside the official list"
+
+# M49 sub-regions that we replace with their intermediate regions
+SPLIT_SUB_REGIONS = {"Sub-Saharan Africa", "Latin America and the Caribbean"}
+
+
+def main():
+    text = DATA.read_text()
+    header, rest = text.split(OFFICIAL)
+    official, unofficial = rest.split(UNOFFICIAL)
+    reader = csv.reader(io.StringIO(official), delimiter="\t", quoting=csv.QUOTE_NONE)
+    existing = {row[0]: row for row in reader if row}
+
+    with urllib.request.urlopen(SOURCE) as response:
+        source = list(csv.DictReader(io.TextIOWrapper(response, encoding="utf-8")))
+
+    rows = []
+    for entry in source:
+        alpha2 = entry["alpha-2"]
+        sub_region = entry["sub-region"]
+        if sub_region in SPLIT_SUB_REGIONS:
+            sub_region = entry["intermediate-region"]
+        codes = [alpha2, entry["alpha-3"], entry["country-code"], sub_region]
+
+        if alpha2 in existing:
+            rows.append(codes + existing.pop(alpha2)[4:])
+        else:
+            name = entry["name"].split(" (")[0].split(",")[0]
+            continent = guess_continent(entry["region"], sub_region)
+            print(f"New: {alpha2} {name} - check continent ({continent}) and names",
+                  file=sys.stderr)
+            rows.append(codes + [continent, name, name, name])
+
+    for alpha2 in existing:
+        print(f"Removed: {alpha2} - consider moving it to the unofficial section",
+              file=sys.stderr)
+
+    with DATA.open("w") as out:
+        out.write(header + OFFICIAL)
+        writer = csv.writer(out, delimiter="\t", lineterminator="\n",
+                            quoting=csv.QUOTE_NONE)
+        writer.writerows(sorted(rows))
+        out.write("\n" + UNOFFICIAL + unofficial)
+
+
+def guess_continent(region, sub_region):
+    """Best guess at the continent, for a human to check."""
+    if region == "Americas":
+        return "South America" if sub_region == "South America" else "North America"
+    return region or "Antarctica"
+
+
+if __name__ == "__main__":
+    main()
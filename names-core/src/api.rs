@@ -0,0 +1,439 @@
+//! The genderize.io and nationalize.io APIs: result types, and the batch
+//! requests themselves, over whatever [HttpClient] the platform provides. Plus
+//! the more detailed single-name requests (including agify.io for age) behind
+//! the app's page for each name.
+
+use std::collections::HashMap;
+
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::Name;
+
+/// Percent probability threshold for an unconditional M or F
+const CERTAIN_THRESHOLD: f32 = 85.0;
+
+/// Percent probability threshold for a probable M or F
+const PROBABLE_THRESHOLD: f32 = 75.0;
+
+/// Standard normal quantile for a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Domain name stem for the age API, api.agify.io
+pub const AGE_DOMAIN: &str = "agify";
+
+/// The API services we query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    Gender,
+    Country,
+}
+
+impl Service {
+    /// Both services
+    pub const ALL: [Service; 2] = [Service::Gender, Service::Country];
+
+    /// Domain name stem, eg "genderize" for api.genderize.io
+    pub fn domain(self) -> &'static str {
+        match self {
+            Service::Gender => "genderize",
+            Service::Country => "nationalize",
+        }
+    }
+}
+
+/// Errors from an API request.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    #[error("API request failed: {0}")]
+    Http(String),
+    #[error("Unexpected API response ({0})")]
+    BadResponse(String),
+    #[error("Invalid API key ({0}) - check the key, or clear it to use the free quota")]
+    InvalidApiKey(String),
+    #[error(
+        "API subscription is not active ({0}) - renew it, or clear the key to use the \
+         free quota"
+    )]
+    SubscriptionInactive(String),
+    #[error("Invalid name ({0})")]
+    InvalidName(String),
+    #[error("API request timed out")]
+    Timeout,
+    #[error("Daily API limit exceeded - try again tomorrow")]
+    LimitExceeded,
+    #[error("Server returned error code {0} ({1})")]
+    ServerError(u16, String),
+}
+
+/// Generic type of an API result: either an [ApiError], or a mapping from first
+/// name to a result type.
+pub type ApiResult<T> = Result<HashMap<Name, T>, ApiError>;
+
+/// Minimal HTTP client, so the API requests can be made over whatever each
+/// platform provides (eg `fetch` in the browser).
+pub trait HttpClient {
+    /// Issue a GET request. Only failing to get any response at all is an
+    /// error: HTTP error statuses are returned as normal responses.
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, ApiError>>;
+}
+
+/// The parts of an HTTP response we care about
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub body: String,
+}
+
+/// How to reach the API services
+#[derive(Debug, Clone, Default)]
+pub struct ApiConfig {
+    /// API key, for a paid plan with a higher quota
+    pub api_key: Option<String>,
+    /// Base URL of a caching proxy (eg names-server) to go via, rather than
+    /// the API services themselves. Requests go to `{base_url}/genderize` etc.
+    pub base_url: Option<String>,
+}
+
+/// Fire off a bulk gender request.
+pub async fn get_genders(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    names: &[Name],
+) -> ApiResult<GenderResult> {
+    let RawGenderResults(results) = fetch(client, config, Service::Gender, names).await?;
+    Ok(results
+        .into_iter()
+        .map(|r| {
+            (
+                r.name,
+                GenderResult {
+                    gender: r.gender,
+                    probability: r.probability,
+                    count: r.count,
+                    offline: false,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Fire off a bulk country request.
+pub async fn get_countries(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    names: &[Name],
+) -> ApiResult<Vec<CountryResult>> {
+    let RawCountryResults(results) =
+        fetch(client, config, Service::Country, names).await?;
+    Ok(results.into_iter().map(|r| (r.name, r.country)).collect())
+}
+
+/// A single API result, along with the raw JSON response, for debugging
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detailed<T> {
+    pub result: T,
+    pub json: String,
+}
+
+/// Look up the gender for a single name, optionally localised to one country
+/// (an ISO 3166 alpha-2 code).
+pub async fn get_gender_detail(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    name: &Name,
+    country: Option<&str>,
+) -> Result<Detailed<GenderResult>, ApiError> {
+    let params: Vec<_> =
+        country.map(|country| ("country_id", country)).into_iter().collect();
+    let url = endpoint_url(
+        config,
+        Service::Gender.domain(),
+        std::slice::from_ref(name),
+        &params,
+    );
+    let (RawGenderResults(results), json) = fetch_url(client, &url).await?;
+    let r = results.into_iter().next().ok_or_else(no_result)?;
+    let result = GenderResult {
+        gender: r.gender,
+        probability: r.probability,
+        count: r.count,
+        offline: false,
+    };
+    Ok(Detailed { result, json })
+}
+
+/// Look up the countries for a single name.
+pub async fn get_countries_detail(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    name: &Name,
+) -> Result<Detailed<Vec<CountryResult>>, ApiError> {
+    let url = url(config, Service::Country, std::slice::from_ref(name));
+    let (RawCountryResults(results), json) = fetch_url(client, &url).await?;
+    let result = results.into_iter().next().ok_or_else(no_result)?.country;
+    Ok(Detailed { result, json })
+}
+
+/// Look up the estimated age for a single name, from agify.io.
+pub async fn get_age(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    name: &Name,
+) -> Result<Detailed<AgeResult>, ApiError> {
+    let url = endpoint_url(config, AGE_DOMAIN, std::slice::from_ref(name), &[]);
+    let (results, json): (Vec<AgeResult>, _) = fetch_url(client, &url).await?;
+    let result = results.into_iter().next().ok_or_else(no_result)?;
+    Ok(Detailed { result, json })
+}
+
+/// Look names up at the API with the given domain name stem (eg "agify"),
+/// optionally localised to one country, returning the raw JSON result for each
+/// name, in order. This is for proxies, which pass the results on as they are.
+pub async fn get_raw(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    domain: &str,
+    names: &[Name],
+    country: Option<&str>,
+) -> Result<Vec<String>, ApiError> {
+    let params: Vec<_> =
+        country.map(|country| ("country_id", country)).into_iter().collect();
+    let url = endpoint_url(config, domain, names, &params);
+    let (results, _): (Vec<serde_json::Value>, _) = fetch_url(client, &url).await?;
+    if results.len() != names.len() {
+        return Err(ApiError::BadResponse(format!(
+            "{} results for {} names",
+            results.len(),
+            names.len()
+        )));
+    }
+    Ok(results.iter().map(|result| result.to_string()).collect())
+}
+
+/// Internal helper function: issue the HTTP request for a batch, and parse the
+/// JSON response.
+async fn fetch<T: DeserializeOwned>(
+    client: &impl HttpClient,
+    config: &ApiConfig,
+    service: Service,
+    names: &[Name],
+) -> Result<T, ApiError> {
+    Ok(fetch_url(client, &url(config, service, names)).await?.0)
+}
+
+/// Internal helper function: issue an HTTP request, and parse the JSON
+/// response (also returned as it is), dealing with the most common error cases.
+async fn fetch_url<T: DeserializeOwned>(
+    client: &impl HttpClient,
+    url: &str,
+) -> Result<(T, String), ApiError> {
+    let response = client.get(url).await?;
+
+    // A successful reponse does not mean an HTTP 200, so turn an unhelpful
+    // server response into an error if appropriate, taking extra care for the
+    // error cases the user might need help interpreting. The body should
+    // explain what went wrong, but fall back to the status text if not.
+    let status = response.status;
+    if status == 200 {
+        return match serde_json::from_str(&response.body) {
+            Ok(result) => Ok((result, response.body)),
+            Err(err) => Err(ApiError::BadResponse(err.to_string())),
+        };
+    }
+    let message = match serde_json::from_str::<RawError>(&response.body) {
+        Ok(RawError { error }) => error,
+        Err(_) => response.status_text,
+    };
+    Err(match status {
+        401 => ApiError::InvalidApiKey(message),
+        402 => ApiError::SubscriptionInactive(message),
+        422 => ApiError::InvalidName(message),
+        429 => ApiError::LimitExceeded,
+        _ => ApiError::ServerError(status, message),
+    })
+}
+
+/// URL for a batch request to one service.
+pub fn url(config: &ApiConfig, service: Service, names: &[Name]) -> String {
+    endpoint_url(config, service.domain(), names, &[])
+}
+
+/// Internal helper function: URL for a request to the API at the given domain,
+/// with any further (unencoded) parameters.
+fn endpoint_url(
+    config: &ApiConfig,
+    domain: &str,
+    names: &[Name],
+    extra_params: &[(&str, &str)],
+) -> String {
+    let mut params = fmt_params(config, names);
+    for (key, value) in extra_params {
+        params.push_str(&format!("&{}={}", key, urlencoding::encode(value)));
+    }
+    match &config.base_url {
+        Some(base) => format!("{}/{}{}", base.trim_end_matches('/'), domain, params),
+        None => format!("https://api.{}.io/{}", domain, params),
+    }
+}
+
+/// Internal helper function: the error for a response with no result in it.
+fn no_result() -> ApiError { ApiError::BadResponse("no result".to_owned()) }
+
+/// Internal helper function: format the parameters. Do it manually, rather than
+/// a crate, since the keys are unencoded, but the values are, and the popular
+/// crates don't have a way of doing that which is simpler than just spelling it
+/// our ourselves here.
+fn fmt_params(config: &ApiConfig, names: &[Name]) -> String {
+    let mut params = String::default();
+    let mut first_param = true;
+    for name in names {
+        let sep = if first_param {
+            first_param = false;
+            '?'
+        } else {
+            '&'
+        };
+        params.push_str(&format!("{}name[]={}", sep, urlencoding::encode(name)));
+    }
+    if let Some(key) = &config.api_key {
+        params.push_str(&format!("&apikey={}", urlencoding::encode(key)));
+    }
+    params
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Gender result for one [Name]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenderResult {
+    pub gender: Option<Gender>,
+    pub probability: f32,
+    pub count: u32,
+    /// Whether this is an offline estimate (see [crate::offline]), rather than
+    /// from the API
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub offline: bool,
+}
+
+impl GenderResult {
+    /// 95% confidence interval for the probability, given the number of people
+    /// behind it (a Wilson score interval), or [None] if there's no data.
+    pub fn interval(&self) -> Option<(f32, f32)> {
+        if self.gender.is_none() || self.count == 0 {
+            return None;
+        }
+        let (p, n) = (f64::from(self.probability), f64::from(self.count));
+        let z2 = Z_95 * Z_95;
+        let denominator = 1.0 + z2 / n;
+        let centre = (p + z2 / (2.0 * n)) / denominator;
+        let half_width =
+            Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        Some((
+            (centre - half_width).max(0.0) as f32,
+            (centre + half_width).min(1.0) as f32,
+        ))
+    }
+
+    /// Plain-text representation of a gender result.
+    pub fn summarised(&self) -> &'static str {
+        let prob = f32::round(self.probability * 100.0);
+        match (self.gender, prob >= CERTAIN_THRESHOLD, prob >= PROBABLE_THRESHOLD) {
+            (Some(Gender::Female), true, _) => "F",
+            (Some(Gender::Female), _, true) => "F?",
+            (Some(Gender::Female), _, _) => "F??",
+            (Some(Gender::Male), true, _) => "M",
+            (Some(Gender::Male), _, true) => "M?",
+            (Some(Gender::Male), _, _) => "M??",
+            _ => "?",
+        }
+    }
+}
+
+/// Gender
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Gender {
+    Female,
+    Male,
+}
+
+impl Gender {
+    /// Single-letter representation
+    pub fn letter(self) -> &'static str {
+        match self {
+            Gender::Female => "F",
+            Gender::Male => "M",
+        }
+    }
+
+    /// Key matching the API representation (also used in the app's edit
+    /// dropdown)
+    pub fn key(self) -> &'static str {
+        match self {
+            Gender::Female => "female",
+            Gender::Male => "male",
+        }
+    }
+
+    /// Inverse of [Gender::key], with anything else meaning no override.
+    pub fn from_key(key: &str) -> Option<Gender> {
+        [Gender::Female, Gender::Male].into_iter().find(|g| g.key() == key)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Single country result for one [Name]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CountryResult {
+    #[serde(rename = "country_id")]
+    pub country: String,
+    pub probability: f32,
+}
+
+/// Age estimate for one [Name]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AgeResult {
+    pub age: Option<u32>,
+    pub count: u32,
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//
+// Internals (raw API representations)
+
+/// Direct representation of a gender API result set.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawGenderResults(pub Vec<RawGenderResult>);
+
+/// Direct representation of a single gender API result.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawGenderResult {
+    pub name: Name,
+    pub gender: Option<Gender>,
+    pub probability: f32,
+    pub count: u32,
+}
+
+/// Direct representation of a country API result set.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawCountryResults(pub Vec<RawCountryResult>);
+
+/// Direct representation of a single country API result.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawCountryResult {
+    pub name: Name,
+    pub country: Vec<CountryResult>,
+}
+
+/// Direct representation of an API error response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawError {
+    pub error: String,
+}
@@ -0,0 +1,247 @@
+//! Group spelling variants of names together, such as "Mohammed", "Muhammad"
+//! and "Mohamed", or "Catherine", "Katherine" and "Kathryn", and combine their
+//! results.
+//!
+//! Two names count as variants if they sound alike, by a phonetic key along
+//! the lines of Double Metaphone (simplified for first names), and are spelt
+//! alike, within an edit distance of half the longer name. Groups are then all
+//! the names linked by a chain of variants. On top of that, the user can merge
+//! groups, or split names out of them, as a list of [Edit]s.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::api::{CountryResult, Gender, GenderResult};
+use crate::Name;
+
+/// A change the user has made to the groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    /// Merge the second name's group into the first's
+    Merge(Name, Name),
+    /// Take a name out of its group, to stand alone
+    Split(Name),
+}
+
+/// Group the names (ignoring case, and duplicates) into variants, then apply
+/// the user's edits in order. Every name is in exactly one group, with the
+/// groups, and the names in each, in order of first appearance.
+pub fn clusters(names: &[Name], edits: &[Edit]) -> Vec<Vec<Name>> {
+    // Distinct names, by lower-case spelling
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut distinct: Vec<(&Name, String)> = Vec::new();
+    for name in names {
+        let lower = name.to_ascii_lowercase();
+        if !index.contains_key(&lower) {
+            index.insert(lower.clone(), distinct.len());
+            distinct.push((name, lower));
+        }
+    }
+
+    // Link the variants, only comparing names with the same phonetic key.
+    let mut parent: Vec<usize> = (0..distinct.len()).collect();
+    let mut by_key: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, (_, lower)) in distinct.iter().enumerate() {
+        by_key.entry(phonetic_key(lower)).or_default().push(i);
+    }
+    for same_key in by_key.values() {
+        for (n, &i) in same_key.iter().enumerate() {
+            for &j in &same_key[..n] {
+                if close_spellings(&distinct[i].1, &distinct[j].1) {
+                    let (root_i, root_j) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[root_i.max(root_j)] = root_i.min(root_j);
+                }
+            }
+        }
+    }
+
+    // Then the user's edits, to each group's list of name indices.
+    let mut group: Vec<usize> =
+        (0..distinct.len()).map(|i| root(&mut parent, i)).collect();
+    let mut next_group = distinct.len();
+    for edit in edits {
+        match edit {
+            Edit::Merge(into, from) => {
+                let find = |name: &Name| index.get(&name.to_ascii_lowercase()).copied();
+                if let (Some(into), Some(from)) = (find(into), find(from)) {
+                    let (into, from) = (group[into], group[from]);
+                    group.iter_mut().filter(|g| **g == from).for_each(|g| *g = into);
+                }
+            }
+            Edit::Split(name) => {
+                if let Some(&i) = index.get(&name.to_ascii_lowercase()) {
+                    group[i] = next_group;
+                    next_group += 1;
+                }
+            }
+        }
+    }
+
+    // Gather up the groups, in order of their first names.
+    let mut clusters: Vec<Vec<Name>> = Vec::new();
+    let mut position: HashMap<usize, usize> = HashMap::new();
+    for (i, (name, _)) in distinct.iter().enumerate() {
+        let at = *position.entry(group[i]).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[at].push((*name).clone());
+    }
+    clusters
+}
+
+/// Phonetic key for a name, so that names which sound alike (more or less) have
+/// the same key. This follows the main rules of Double Metaphone, with a single
+/// key rather than two, and some simplifications for first names: vowels are
+/// dropped, except at the start, consonants that sound alike share a code, and
+/// double consonants count once.
+pub fn phonetic_key(name: &str) -> String {
+    let letters: Vec<u8> = name
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_lowercase())
+        .collect();
+    let at = |i: usize| letters.get(i).copied().unwrap_or_default();
+    let mut key = String::new();
+    let mut i = 0;
+    while i < letters.len() {
+        let (prev, c, next) = (i.checked_sub(1).map_or(0, at), at(i), at(i + 1));
+        // Double consonants sound like one.
+        if c == prev && !is_vowel(c) {
+            i += 1;
+            continue;
+        }
+        let (code, len) = match c {
+            b'y' if i == 0 && is_vowel(next) => ("J", 1),
+            _ if is_vowel(c) => (if i == 0 { "A" } else { "" }, 1),
+            b'p' if next == b'h' => ("F", 2),
+            b'b' | b'p' => ("P", 1),
+            b'c' if next == b'h' && at(i + 2) == b'r' => ("K", 2),
+            b'c' if next == b'h' => ("X", 2),
+            b'c' if matches!(next, b'e' | b'i' | b'y') => ("S", 1),
+            b'c' if next == b'k' => ("K", 2),
+            b'c' | b'k' | b'q' => ("K", 1),
+            b'd' | b't' if next == b'h' => ("T", 2),
+            b'd' if next == b'j' => ("J", 2),
+            b'd' | b't' => ("T", 1),
+            b'f' | b'v' => ("F", 1),
+            b'g' if next == b'h' => (if i == 0 { "K" } else { "" }, 2),
+            b'g' if matches!(next, b'e' | b'i' | b'y') => ("J", 1),
+            b'g' => ("K", 1),
+            b'h' if (i == 0 || is_vowel(prev)) && is_vowel(next) => ("H", 1),
+            b'j' => ("J", 1),
+            b's' if next == b'c' && at(i + 2) == b'h' => ("X", 3),
+            b's' if next == b'h' => ("X", 2),
+            b's' | b'z' => ("S", 1),
+            b'w' if is_vowel(next) => ("F", 1),
+            b'x' => ("KS", 1),
+            b'l' => ("L", 1),
+            b'm' => ("M", 1),
+            b'n' => ("N", 1),
+            b'r' => ("R", 1),
+            _ => ("", 1),
+        };
+        key.push_str(code);
+        i += len;
+    }
+    key
+}
+
+/// Levenshtein distance between two names, in bytes, ignoring case.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.bytes().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.bytes().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Whether two names are variants of each other, as described above.
+pub fn similar(a: &str, b: &str) -> bool {
+    phonetic_key(a) == phonetic_key(b) && close_spellings(a, b)
+}
+
+/// Combined gender for a group, weighting each name's result by the number of
+/// people behind it, or [None] if there are no results at all.
+pub fn combine_genders<'a>(
+    results: impl IntoIterator<Item = &'a GenderResult>,
+) -> Option<GenderResult> {
+    let (mut female, mut count, mut offline, mut any) = (0.0, 0_u32, false, false);
+    for result in results {
+        let share = match result.gender {
+            Some(Gender::Female) => result.probability,
+            Some(Gender::Male) => 1.0 - result.probability,
+            None => 0.5,
+        };
+        female += f64::from(share) * f64::from(result.count);
+        count = count.saturating_add(result.count);
+        offline |= result.offline;
+        any = true;
+    }
+    if !any {
+        return None;
+    }
+    let share = if count == 0 { 0.5 } else { (female / f64::from(count)) as f32 };
+    let (gender, probability) = if share > 0.5 {
+        (Some(Gender::Female), share)
+    } else if share < 0.5 {
+        (Some(Gender::Male), 1.0 - share)
+    } else {
+        (None, 0.0)
+    };
+    Some(GenderResult { gender, probability, count, offline })
+}
+
+/// Combined countries for a group, weighting each name's results by the given
+/// number of people (or equally, if there are none), most likely first.
+pub fn combine_countries<'a>(
+    results: impl IntoIterator<Item = (&'a [CountryResult], u32)>,
+) -> Vec<CountryResult> {
+    let results: Vec<_> = results.into_iter().collect();
+    let total: u64 = results.iter().map(|&(_, weight)| u64::from(weight)).sum();
+    let mut combined: Vec<CountryResult> = Vec::new();
+    for (countries, weight) in &results {
+        let weight = match total {
+            0 => 1.0 / results.len() as f32,
+            _ => (f64::from(*weight) / total as f64) as f32,
+        };
+        for country in countries.iter() {
+            let probability = country.probability * weight;
+            match combined.iter_mut().find(|c| c.country == country.country) {
+                Some(c) => c.probability += probability,
+                None => combined.push(CountryResult { probability, ..country.clone() }),
+            }
+        }
+    }
+    combined.sort_by(|a, b| {
+        b.probability.partial_cmp(&a.probability).unwrap_or(Ordering::Equal)
+    });
+    combined
+}
+
+/// Internal helper function: whether two (lower-case) spellings are within the
+/// edit distance for variants.
+fn close_spellings(a: &str, b: &str) -> bool {
+    edit_distance(a, b) * 2 <= a.len().max(b.len())
+}
+
+/// Internal helper function: whether a (lower-case) letter is a vowel, for the
+/// purposes of [phonetic_key].
+fn is_vowel(c: u8) -> bool { matches!(c, b'a' | b'e' | b'i' | b'o' | b'u' | b'y') }
+
+/// Internal helper function: the root of an element in a union-find forest,
+/// flattening the path to it along the way.
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
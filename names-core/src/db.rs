@@ -0,0 +1,84 @@
+//! In-memory cache of API results, for every name looked up (or being looked
+//! up) so far. This holds no requests itself: the caller drives a
+//! [Queue](crate::queue::Queue) to fetch whatever this says is missing, and
+//! stores what comes back.
+
+use im_rc::HashMap;
+
+use crate::api::Service;
+use crate::queue::Queue;
+use crate::{AllResults, Name, Remote};
+
+/// Database of cached or in-flight API results
+pub type Db = HashMap<Name, AllResults>;
+
+/// Picks out the results for one service from all of a name's results
+pub type Selector<T> = fn(&mut AllResults) -> &mut Remote<T>;
+
+/// Add entries, all still loading, for any of these names not already cached,
+/// and return those names, which need looking up.
+pub fn insert_new(db: &mut Db, names: &[Name]) -> Vec<Name> {
+    let mut new = Vec::new();
+    for name in names {
+        if db.get(name).is_none() {
+            db.insert(name.clone(), AllResults::default());
+            new.push(name.clone());
+        }
+    }
+    new
+}
+
+/// Store the results for one service, for any of the names still cached.
+pub fn store<T>(
+    db: &mut Db,
+    results: impl IntoIterator<Item = (Name, T)>,
+    selector: Selector<T>,
+) {
+    for (name, item) in results {
+        if let Some(results) = db.get_mut(&name) {
+            *selector(results) = Remote::Success(item);
+        }
+    }
+}
+
+/// Note that the lookups for one service failed, for any of the names still
+/// cached.
+pub fn fail<T>(db: &mut Db, names: &[Name], selector: Selector<T>) {
+    for name in names {
+        if let Some(results) = db.get_mut(name) {
+            *selector(results) = Remote::Error;
+        }
+    }
+}
+
+/// Set these names' failed lookups for one service (and for genders, any
+/// offline estimates) back to loading, and return the names, which need
+/// looking up again.
+pub fn reset_failed(db: &mut Db, service: Service, names: &[Name]) -> Vec<Name> {
+    let mut reset = Vec::new();
+    for name in names {
+        let failed = match (db.get_mut(name), service) {
+            (Some(results), Service::Gender) => match &results.gender {
+                Remote::Success(result) if result.offline => {
+                    results.gender = Remote::Loading;
+                    true
+                }
+                _ => results.gender.reset_if_failed(),
+            },
+            (Some(results), Service::Country) => results.country.reset_if_failed(),
+            (None, _) => false,
+        };
+        if failed {
+            reset.push(name.clone());
+        }
+    }
+    reset
+}
+
+/// Remove cache entries for any of these names that are (still) no longer
+/// wanted, so if they are entered again, they get queried afresh.
+pub fn forget_unwanted<H, D>(db: &mut Db, queue: &Queue<H, D>, names: &[Name]) {
+    for name in names.iter().filter(|name| !queue.is_wanted(name)) {
+        db.remove(name);
+    }
+}
@@ -0,0 +1,382 @@
+//! ISO 3166 country data (names, codes and flags), along with the UN M49
+//! geographical regions each country belongs to.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use SubRegion::*;
+
+use crate::api::CountryResult;
+
+/// Map a two-letter country code to everything we know about that country.
+/// Anything missing from the table is logged (once per code), so it can be
+/// added to data/iso3166.tsv.
+pub fn country(code: &str) -> Option<&'static Country> {
+    let country = COUNTRY.get(code);
+    if country.is_none() {
+        log_unknown(code);
+    }
+    country
+}
+
+thread_local! {
+    /// Unknown country codes we have already logged
+    static LOGGED_UNKNOWN: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Log an unknown country code, if we haven't already.
+fn log_unknown(code: &str) {
+    LOGGED_UNKNOWN.with(|logged| {
+        if logged.borrow_mut().insert(code.to_owned()) {
+            tracing::warn!(
+                "Unknown country code {:?} - add it to names-core/data/iso3166.tsv",
+                code
+            );
+        }
+    });
+}
+
+/// Every country we know about, in no particular order.
+pub fn all() -> impl Iterator<Item = &'static Country> { COUNTRY.values() }
+
+/// Flag emoji for a two-letter country code. This is just the pair of
+/// corresponding regional indicator symbols, so works for any well-formed code,
+/// including ones missing from our table (although whether it renders as a
+/// flag is down to the browser's font).
+pub fn flag(code: &str) -> Option<String> {
+    if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    code.chars().map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32))).collect()
+}
+
+/// Everything we know about one country.
+#[derive(Debug)]
+pub struct Country {
+    /// ISO 3166 alpha-2 code, eg "FR"
+    pub alpha2: &'static str,
+    /// ISO 3166 alpha-3 code, eg "FRA"
+    pub alpha3: &'static str,
+    /// ISO 3166 numeric code (the same as the UN M49 code), eg 250, or [None]
+    /// for user-assigned codes that have no numeric equivalent
+    pub numeric: Option<u16>,
+    /// Short English name
+    pub name: &'static str,
+    /// UN M49 sub-region ([None] only for Antarctica, which M49 leaves out)
+    pub sub_region: Option<SubRegion>,
+    /// Continent, using the seven-continent model
+    pub continent: Continent,
+}
+
+impl Country {
+    /// Constructor, so the generated table stays reasonably compact.
+    const fn new(
+        alpha2: &'static str,
+        alpha3: &'static str,
+        numeric: Option<u16>,
+        name: &'static str,
+        sub_region: Option<SubRegion>,
+        continent: Continent,
+    ) -> Self {
+        Country { alpha2, alpha3, numeric, name, sub_region, continent }
+    }
+
+    /// Flag emoji
+    pub fn flag(&self) -> String { flag(self.alpha2).unwrap_or_default() }
+
+    /// All the codes for this country, eg "FR / FRA / 250"
+    pub fn codes(&self) -> String {
+        match self.numeric {
+            Some(numeric) => {
+                format!("{} / {} / {:03}", self.alpha2, self.alpha3, numeric)
+            }
+            None => format!("{} / {}", self.alpha2, self.alpha3),
+        }
+    }
+
+    /// UN M49 region
+    pub fn region(&self) -> Option<Region> { self.sub_region.map(SubRegion::region) }
+
+    /// Short name in one of the other languages we have names for ("fr" or
+    /// "de"), if we have it
+    pub fn translated_name(&self, language: &str) -> Option<&'static str> {
+        match language {
+            "fr" => COUNTRY_FR.get(self.alpha2).copied(),
+            "de" => COUNTRY_DE.get(self.alpha2).copied(),
+            _ => None,
+        }
+    }
+
+    /// Localised label for the group this country falls into at the given
+    /// granularity.
+    pub fn group(
+        &self,
+        grouping: Grouping,
+        labels: &impl Labels,
+    ) -> Option<&'static str> {
+        match grouping {
+            Grouping::Country => Some(labels.country(self)),
+            Grouping::SubRegion => self.sub_region.map(|s| labels.sub_region(s)),
+            Grouping::Region => self.region().map(|r| labels.region(r)),
+            Grouping::Continent => Some(labels.continent(self.continent)),
+        }
+    }
+}
+
+/// UN M49 region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Africa,
+    Americas,
+    Asia,
+    Europe,
+    Oceania,
+}
+
+impl Region {
+    /// English name
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Africa => "Africa",
+            Region::Americas => "Americas",
+            Region::Asia => "Asia",
+            Region::Europe => "Europe",
+            Region::Oceania => "Oceania",
+        }
+    }
+}
+
+/// UN M49 sub-region. Where M49 further splits a sub-region into intermediate
+/// regions (Sub-Saharan Africa, and Latin America and the Caribbean), we use
+/// those instead, since eg "Eastern Africa" is far more useful than
+/// "Sub-Saharan Africa" when summarising where a name is popular.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubRegion {
+    NorthernAfrica,
+    EasternAfrica,
+    MiddleAfrica,
+    SouthernAfrica,
+    WesternAfrica,
+    Caribbean,
+    CentralAmerica,
+    SouthAmerica,
+    NorthernAmerica,
+    CentralAsia,
+    EasternAsia,
+    SouthEasternAsia,
+    SouthernAsia,
+    WesternAsia,
+    EasternEurope,
+    NorthernEurope,
+    SouthernEurope,
+    WesternEurope,
+    AustraliaAndNewZealand,
+    Melanesia,
+    Micronesia,
+    Polynesia,
+}
+
+impl SubRegion {
+    /// English name
+    pub fn name(self) -> &'static str {
+        match self {
+            NorthernAfrica => "Northern Africa",
+            EasternAfrica => "Eastern Africa",
+            MiddleAfrica => "Middle Africa",
+            SouthernAfrica => "Southern Africa",
+            WesternAfrica => "Western Africa",
+            Caribbean => "Caribbean",
+            CentralAmerica => "Central America",
+            SouthAmerica => "South America",
+            NorthernAmerica => "Northern America",
+            CentralAsia => "Central Asia",
+            EasternAsia => "Eastern Asia",
+            SouthEasternAsia => "South-eastern Asia",
+            SouthernAsia => "Southern Asia",
+            WesternAsia => "Western Asia",
+            EasternEurope => "Eastern Europe",
+            NorthernEurope => "Northern Europe",
+            SouthernEurope => "Southern Europe",
+            WesternEurope => "Western Europe",
+            AustraliaAndNewZealand => "Australia and New Zealand",
+            Melanesia => "Melanesia",
+            Micronesia => "Micronesia",
+            Polynesia => "Polynesia",
+        }
+    }
+
+    /// The region containing this sub-region
+    pub fn region(self) -> Region {
+        match self {
+            NorthernAfrica | EasternAfrica | MiddleAfrica | SouthernAfrica
+            | WesternAfrica => Region::Africa,
+            Caribbean | CentralAmerica | SouthAmerica | NorthernAmerica => {
+                Region::Americas
+            }
+            CentralAsia | EasternAsia | SouthEasternAsia | SouthernAsia | WesternAsia => {
+                Region::Asia
+            }
+            EasternEurope | NorthernEurope | SouthernEurope | WesternEurope => {
+                Region::Europe
+            }
+            AustraliaAndNewZealand | Melanesia | Micronesia | Polynesia => {
+                Region::Oceania
+            }
+        }
+    }
+}
+
+/// Continent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Continent {
+    Africa,
+    Antarctica,
+    Asia,
+    Europe,
+    NorthAmerica,
+    Oceania,
+    SouthAmerica,
+}
+
+impl Continent {
+    /// English name
+    pub fn name(self) -> &'static str {
+        match self {
+            Continent::Africa => "Africa",
+            Continent::Antarctica => "Antarctica",
+            Continent::Asia => "Asia",
+            Continent::Europe => "Europe",
+            Continent::NorthAmerica => "North America",
+            Continent::Oceania => "Oceania",
+            Continent::SouthAmerica => "South America",
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Labels for countries and regions, in some language.
+pub trait Labels {
+    fn country(&self, country: &Country) -> &'static str;
+    fn sub_region(&self, sub_region: SubRegion) -> &'static str;
+    fn region(&self, region: Region) -> &'static str;
+    fn continent(&self, continent: Continent) -> &'static str;
+    /// Label for results we can't place
+    fn unknown(&self) -> &'static str;
+}
+
+/// The English names from our tables
+pub struct English;
+
+impl Labels for English {
+    fn country(&self, country: &Country) -> &'static str { country.name }
+
+    fn sub_region(&self, sub_region: SubRegion) -> &'static str { sub_region.name() }
+
+    fn region(&self, region: Region) -> &'static str { region.name() }
+
+    fn continent(&self, continent: Continent) -> &'static str { continent.name() }
+
+    fn unknown(&self) -> &'static str { "Unknown" }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// Granularity at which to present country results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    Country,
+    SubRegion,
+    Region,
+    Continent,
+}
+
+impl Grouping {
+    /// All groupings, finest first
+    pub const ALL: [Grouping; 4] =
+        [Grouping::Country, Grouping::SubRegion, Grouping::Region, Grouping::Continent];
+
+    /// English name
+    pub fn name(self) -> &'static str {
+        match self {
+            Grouping::Country => "Country",
+            Grouping::SubRegion => "Sub-region",
+            Grouping::Region => "Region",
+            Grouping::Continent => "Continent",
+        }
+    }
+}
+
+/// Country results aggregated at some [Grouping] granularity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub label: String,
+    pub flag: Option<String>,
+    /// Extra detail for a tooltip
+    pub title: String,
+    pub probability: f32,
+}
+
+/// Aggregate country results into [Bucket]s, most likely first, with the given
+/// labels.
+pub fn buckets(
+    countries: &[CountryResult],
+    grouping: Grouping,
+    labels: &impl Labels,
+) -> Vec<Bucket> {
+    let mut buckets: Vec<Bucket> = Vec::new();
+    for c in countries {
+        let (label, flag, title) = match (grouping, country(&c.country)) {
+            (Grouping::Country, Some(country)) => {
+                let name = labels.country(country);
+                let title = format!("{} ({})", name, country.codes());
+                (name.to_owned(), Some(country.flag()), title)
+            }
+            (Grouping::Country, None) => {
+                (c.country.clone(), flag(&c.country), c.country.clone())
+            }
+            (_, country) => {
+                let label = country.and_then(|country| country.group(grouping, labels));
+                let label = label.unwrap_or_else(|| labels.unknown()).to_owned();
+                (label.clone(), None, label)
+            }
+        };
+
+        // Merge into any existing bucket with the same label. (Not for
+        // individual countries though, which are already distinct, even if
+        // they share a short name, like the two Congos.)
+        let existing = match grouping {
+            Grouping::Country => None,
+            _ => buckets.iter_mut().find(|b| b.label == label),
+        };
+        match existing {
+            Some(bucket) => bucket.probability += c.probability,
+            None => {
+                buckets.push(Bucket { label, flag, title, probability: c.probability })
+            }
+        }
+    }
+    buckets.sort_by(|a, b| {
+        b.probability.partial_cmp(&a.probability).unwrap_or(Ordering::Equal)
+    });
+    buckets
+}
+
+/// The probability not accounted for by the given buckets.
+pub fn remainder(buckets: &[Bucket]) -> f32 {
+    let total: f32 = buckets.iter().map(|b| b.probability).sum();
+    // Guard against rounding errors from the API taking us fractionally below
+    // zero.
+    (1.0 - total).max(0.0)
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+// The table itself, `const COUNTRY: phf::Map<&'static str, Country>`, and the
+// French and German short names (`const COUNTRY_FR` and `COUNTRY_DE`, mapping
+// alpha-2 codes to `&'static str`s), are generated by build.rs from
+// data/iso3166.tsv.
+include!(concat!(env!("OUT_DIR"), "/iso3166.rs"));
+include!(concat!(env!("OUT_DIR"), "/country_fr.rs"));
+include!(concat!(env!("OUT_DIR"), "/country_de.rs"));
@@ -0,0 +1,62 @@
+//! Platform-independent core of the names app: the genderize.io and
+//! nationalize.io APIs, name normalisation, nicknames and variants, country
+//! data, offline estimates, the request queue, and the results cache. Nothing
+//! here depends on the browser, so it can be shared with native tools, and
+//! tested natively.
+#![warn(rust_2018_idioms, unused)]
+
+pub mod api;
+pub mod cluster;
+pub mod db;
+pub mod iso3166;
+#[cfg(feature = "ureq")]
+pub mod native;
+pub mod nicknames;
+pub mod normalise;
+pub mod offline;
+pub mod queue;
+
+use kstring::KString;
+
+use crate::api::{CountryResult, GenderResult};
+
+/// A person's first name. Since these are typically short, using a more
+/// space-efficient String variant is a win.
+pub type Name = KString;
+
+/// List of names
+pub type Names = Vec<Name>;
+
+/// Representation of the result of an API request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Remote<T> {
+    Loading,
+    Error,
+    Success(T),
+}
+
+/// Result of both gender and country API requests for one name
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct AllResults {
+    pub gender: Remote<GenderResult>,
+    pub country: Remote<Vec<CountryResult>>,
+}
+
+// Deriving this with `#[default]` needs a newer toolchain than the one pinned
+// in flake.lock, and a plain derive would require `T: Default`
+// (https://github.com/rust-lang/rust/issues/26925).
+#[allow(clippy::derivable_impls)]
+impl<T> Default for Remote<T> {
+    fn default() -> Self { Remote::Loading }
+}
+
+impl<T> Remote<T> {
+    /// Set a failed result back to loading, returning whether it had failed.
+    pub fn reset_if_failed(&mut self) -> bool {
+        let failed = matches!(self, Remote::Error);
+        if failed {
+            *self = Remote::Loading;
+        }
+        failed
+    }
+}
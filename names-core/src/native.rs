@@ -0,0 +1,71 @@
+//! [HttpClient] for native builds, using blocking `ureq` requests (with the
+//! `ureq` feature).
+
+use std::error::Error;
+use std::io;
+use std::time::Duration;
+
+use futures::future::LocalBoxFuture;
+
+use crate::api::{ApiError, HttpClient, HttpResponse};
+
+/// Blocking HTTP client. The futures it returns do all their work on the
+/// first poll, so are only suitable for running one request at a time per
+/// thread (eg with `futures::executor::block_on`).
+#[derive(Clone)]
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl UreqClient {
+    /// New client, giving up on each request after `timeout` seconds.
+    pub fn new(timeout: u32) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(timeout.into()))
+            .build();
+        UreqClient { agent }
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, ApiError>> {
+        Box::pin(async move {
+            // HTTP error statuses aren't errors at this level.
+            let response = match self.agent.get(url).call() {
+                Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+                Err(ureq::Error::Transport(err)) => return Err(transport_error(&err)),
+            };
+            Ok(HttpResponse {
+                status: response.status(),
+                status_text: response.status_text().to_owned(),
+                body: response.into_string().map_err(|err| {
+                    if timed_out(&err) {
+                        ApiError::Timeout
+                    } else {
+                        ApiError::Http(err.to_string())
+                    }
+                })?,
+            })
+        })
+    }
+}
+
+/// Internal helper function: the error for a request that didn't get as far as
+/// a response. Not the whole error, since the URL might include the API key.
+fn transport_error(err: &ureq::Transport) -> ApiError {
+    let io_error = err.source().and_then(|source| source.downcast_ref::<io::Error>());
+    if matches!(io_error, Some(io_error) if timed_out(io_error)) {
+        return ApiError::Timeout;
+    }
+    let message = err.message().map(str::to_owned);
+    ApiError::Http(message.unwrap_or_else(|| err.kind().to_string()))
+}
+
+/// Internal helper function: whether an I/O error is from giving up waiting.
+/// Some platforms report socket timeouts as `WouldBlock`.
+fn timed_out(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+}
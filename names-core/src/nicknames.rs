@@ -0,0 +1,38 @@
+//! Nicknames and diminutives, such as "Bob" for "Robert", or "Liz" for
+//! "Elizabeth". These often have far fewer results behind them than their full
+//! forms, or a skewed distribution, so the full form is worth looking up too.
+
+use crate::api::GenderResult;
+use crate::Remote;
+
+/// Number of people behind a nickname's own result below which its full
+/// form's result is preferred, if that has more. Any more, and the nickname's
+/// own result is taken to stand on its own, since a nickname can lean the
+/// other way from its full form (eg "Sasha" and "Alexander").
+pub const LOW_COUNT: u32 = 100;
+
+/// The full form of a nickname (in any case), if it is one.
+pub fn full_form(name: &str) -> Option<&'static str> {
+    NICKNAMES.get(name.to_ascii_lowercase().as_str()).copied()
+}
+
+/// Whether to go with the full form's results rather than the nickname's own:
+/// if the nickname's own result has too little data behind it (fewer than
+/// [LOW_COUNT] people) and the full form's has more, or the nickname's own
+/// lookup failed. Until both are in, stick with the nickname.
+pub fn prefer_full_form(own: &Remote<GenderResult>, full: &Remote<GenderResult>) -> bool {
+    match (own, full) {
+        (Remote::Success(own), Remote::Success(full)) => {
+            own.count < LOW_COUNT && full.count > own.count
+        }
+        (Remote::Error, Remote::Success(_)) => true,
+        _ => false,
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+// The table itself, `const NICKNAMES: phf::Map<&'static str, &'static str>`,
+// mapping lower-case nicknames to full forms, is generated by build.rs from
+// data/nicknames.tsv.
+include!(concat!(env!("OUT_DIR"), "/nicknames.rs"));
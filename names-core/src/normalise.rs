@@ -0,0 +1,66 @@
+//! Turn whatever the user typed or pasted into the names we query.
+//!
+//! The rules, for any text at all (mixed scripts, emoji, stray control
+//! characters, and so on):
+//!
+//! 1. Text is split into lines at "\n", "\r\n", or a lone "\r".
+//! 2. Invisible formatting characters (zero-width spaces and joiners, byte
+//!    order marks, soft hyphens, and bidi controls) are dropped, so they can't
+//!    split a name in two.
+//! 3. What's left is transliterated to ASCII, eg "Zoë" to "Zoe", and "Анна" to
+//!    "Anna".
+//! 4. The name is the first run of ASCII letters, skipping anything before it,
+//!    such as list numbering or emoji. So "1. Jean-Luc" becomes "Jean", since
+//!    the API only handles single words.
+//! 5. A line with no letters at all, or whose name is [IGNORED_NAME], is
+//!    rejected.
+//!
+//! So every name is a non-empty run of ASCII letters, normalising a name again
+//! leaves it unchanged, and nothing here panics, whatever the input.
+
+use kstring::KString;
+use unidecode::unidecode;
+
+use crate::Name;
+
+/// Name to ignore, so if this is the column title in a source spreadsheet,
+/// where the entire column is pasted in, we strip it out of the query. Sorry if
+/// your name is "First".
+pub const IGNORED_NAME: &str = "First";
+
+/// Split text into lines, at any of "\n", "\r\n", or a lone "\r".
+pub fn split_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split('\n').flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+}
+
+/// Parse one line of input into a name to query, or [None] if the line should
+/// be ignored.
+pub fn parse_line(line: &str) -> Option<Name> {
+    normalise_name(line).filter(|name| name.as_str() != IGNORED_NAME)
+}
+
+/// Normalise a name to a single ASCII word (all the API seems to handle), or
+/// [None] if there are no letters in it.
+pub fn normalise_name(name: &str) -> Option<Name> {
+    let visible: String = name.chars().filter(|&c| !is_invisible(c)).collect();
+    let ascii_name = unidecode(&visible);
+    let first_word = ascii_name
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| !word.is_empty())?;
+    Some(KString::from_ref(first_word))
+}
+
+/// Internal helper function: whether a character is an invisible formatting
+/// character, which transliteration might otherwise turn into a word break.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
@@ -0,0 +1,178 @@
+//! Offline estimates of gender, for when the API is out of quota or out of
+//! reach: a frequency table of the most common first names, built from public
+//! name statistics by `names offline-table` (in names-cli).
+//!
+//! The table is stored compactly, since it's downloaded on demand:
+//!
+//! - the magic bytes `NAMES1\n`
+//! - the number of entries
+//! - for each entry, sorted by name (lower-case ASCII letters only):
+//!   - the number of leading bytes shared with the previous name
+//!   - the length of the rest of the name, then the rest itself
+//!   - the number of females, then males, with the name
+//!
+//! with every number an unsigned LEB128 varint.
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+use crate::api::{Gender, GenderResult};
+
+/// Identifies a table, and the version of the format
+const MAGIC: &[u8] = b"NAMES1\n";
+
+/// Problems decoding a table
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    #[error("not an offline names table")]
+    BadMagic,
+    #[error("offline names table is truncated")]
+    Truncated,
+    #[error("offline names table has an invalid name")]
+    BadName,
+}
+
+/// Frequency table of first names, by gender
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    /// Lower-case name, and female and male counts, sorted by name
+    entries: Vec<(String, u32, u32)>,
+}
+
+impl Table {
+    /// Build a table of the `limit` most common names, from counts of females
+    /// and males with each name. Counts for the same name (in any case) are
+    /// added together.
+    pub fn from_counts(
+        counts: impl IntoIterator<Item = (String, u32, u32)>,
+        limit: usize,
+    ) -> Self {
+        let mut entries: Vec<(String, u32, u32)> = Vec::new();
+        let mut counts: Vec<_> = counts
+            .into_iter()
+            .map(|(name, female, male)| (name.to_ascii_lowercase(), female, male))
+            .filter(|(name, ..)| is_valid(name))
+            .collect();
+        counts.sort();
+        for (name, female, male) in counts {
+            match entries.last_mut() {
+                Some(last) if last.0 == name => {
+                    last.1 = last.1.saturating_add(female);
+                    last.2 = last.2.saturating_add(male);
+                }
+                _ => entries.push((name, female, male)),
+            }
+        }
+
+        // Keep the most common, then put them back in order of name.
+        entries.sort_by_key(|&(_, female, male)| {
+            std::cmp::Reverse(u64::from(female) + u64::from(male))
+        });
+        entries.truncate(limit);
+        entries.sort();
+        Table { entries }
+    }
+
+    /// Decode a table in the format described above.
+    pub fn decode(bytes: &[u8]) -> Result<Self, TableError> {
+        let mut bytes = bytes.strip_prefix(MAGIC).ok_or(TableError::BadMagic)?;
+        let len = varint(&mut bytes)? as usize;
+        let mut entries: Vec<(String, u32, u32)> = Vec::with_capacity(len.min(1 << 16));
+        for _ in 0..len {
+            let shared = varint(&mut bytes)? as usize;
+            let rest = varint(&mut bytes)? as usize;
+            if rest > bytes.len() {
+                return Err(TableError::Truncated);
+            }
+            let (rest, tail) = bytes.split_at(rest);
+            bytes = tail;
+            let previous = entries.last().map_or("", |(name, ..)| name.as_str());
+            let prefix = previous.get(..shared).ok_or(TableError::BadName)?;
+            let rest = std::str::from_utf8(rest).map_err(|_| TableError::BadName)?;
+            let name = format!("{prefix}{rest}");
+            if !is_valid(&name) || previous >= name.as_str() {
+                return Err(TableError::BadName);
+            }
+            let female = varint(&mut bytes)?;
+            let male = varint(&mut bytes)?;
+            entries.push((name, female, male));
+        }
+        Ok(Table { entries })
+    }
+
+    /// Encode the table in the format described above.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        put_varint(&mut bytes, self.entries.len() as u32);
+        let mut previous = "";
+        for (name, female, male) in &self.entries {
+            let shared =
+                previous.bytes().zip(name.bytes()).take_while(|(a, b)| a == b).count();
+            put_varint(&mut bytes, shared as u32);
+            put_varint(&mut bytes, (name.len() - shared) as u32);
+            bytes.extend_from_slice(&name.as_bytes()[shared..]);
+            put_varint(&mut bytes, *female);
+            put_varint(&mut bytes, *male);
+            previous = name;
+        }
+        bytes
+    }
+
+    /// Number of names in the table
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Whether the table has no names at all
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Estimate the gender for a name, in the same terms as the API, if it's in
+    /// the table.
+    pub fn lookup(&self, name: &str) -> Option<GenderResult> {
+        let name = name.to_ascii_lowercase();
+        let index = self
+            .entries
+            .binary_search_by(|(entry, ..)| entry.as_str().cmp(&name))
+            .ok()?;
+        let (_, female, male) = self.entries[index];
+        let count = female.saturating_add(male);
+        let (gender, majority) = match female.cmp(&male) {
+            Ordering::Greater => (Some(Gender::Female), female),
+            Ordering::Less => (Some(Gender::Male), male),
+            Ordering::Equal => (None, female),
+        };
+        // Rounded to two decimal places, like the API
+        let probability = match count {
+            0 => 0.0,
+            _ => f32::round(majority as f32 / count as f32 * 100.0) / 100.0,
+        };
+        Some(GenderResult { gender, probability, count, offline: true })
+    }
+}
+
+/// Internal helper function: whether a name can go in a table.
+fn is_valid(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+/// Internal helper function: read an unsigned LEB128 varint.
+fn varint(bytes: &mut &[u8]) -> Result<u32, TableError> {
+    let mut value: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(TableError::Truncated)?;
+        *bytes = rest;
+        value |= u32::from(byte & 0x7f).checked_shl(shift).unwrap_or(0);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(TableError::Truncated)
+}
+
+/// Internal helper function: write an unsigned LEB128 varint.
+fn put_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
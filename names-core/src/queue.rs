@@ -0,0 +1,720 @@
+//! Request scheduling: which names to send to each API service, in what
+//! batches, and how to react when a request fails. Lookups for the page for a
+//! single name (of whatever type `D` the caller describes them with) go through
+//! the same pausing and backing off, one at a time.
+//!
+//! This does no I/O itself, and doesn't know the time: callers pass in the
+//! current time, carry out the requests, and report back how they went. Each
+//! in-flight batch also carries a handle of type `H`, which the caller can use
+//! to cancel it (eg an `AbortController` in the browser).
+
+use std::collections::{HashSet, VecDeque};
+use std::rc::Rc;
+
+use crate::api::{ApiError, Service};
+use crate::{Name, Names};
+
+/// Maximum number of queries per batched API call
+pub const API_CHUNKS: usize = 10;
+
+/// Maximum number of concurrent requests per service
+const MAX_CONCURRENT: usize = 2;
+
+/// Number of milliseconds to wait before retrying, the first time the API says
+/// we're sending too many requests. This doubles each further time in a row.
+const INITIAL_BACKOFF: u32 = 2_000;
+
+/// Longest backoff, in milliseconds. If the API is still rejecting requests
+/// after this, we give up on everything queued.
+const MAX_BACKOFF: u32 = 32_000;
+
+/// Weight given to each new batch latency in the running average used for
+/// estimating how long is left
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Book-keeping for all API requests, queued or in progress.
+pub struct Queue<H, D = ()> {
+    /// The names currently wanted, and the list they were taken from
+    wanted: HashSet<Name>,
+    wanted_from: Option<Rc<Names>>,
+    /// Whether any queued names might no longer be wanted
+    needs_pruning: bool,
+    /// Whether the user has paused sending any more requests
+    paused: bool,
+    /// Whether we have lost the network connection, in which case we also
+    /// hold off
+    offline: bool,
+    /// Number of milliseconds to wait for each request
+    timeout: u32,
+    /// The names currently on screen, which are fetched first
+    visible: HashSet<Name>,
+    gender: Scheduler<H>,
+    country: Scheduler<H>,
+    detail: Scheduler<H, D>,
+    next_id: usize,
+    /// Number of name lookups never sent, since the names were deleted first
+    pub saved: usize,
+}
+
+/// Request scheduling for one API service. Names queued by every edit are
+/// coalesced into full batches where possible, and only a limited number of
+/// requests are in flight at once.
+pub struct Scheduler<H, T = Name> {
+    /// Names waiting to be sent, oldest first
+    pending: VecDeque<T>,
+    /// Batches currently being fetched
+    in_flight: Vec<InFlight<H, T>>,
+    /// Current number of names per batch. This halves after a failed request,
+    /// so any problem name holds up fewer others, and recovers after
+    /// successful ones.
+    batch_size: usize,
+    /// Most names per batch
+    max_batch: usize,
+    /// Current backoff delay, if the API is rejecting our requests
+    backoff: Option<u32>,
+    /// Whether we are currently waiting out that backoff
+    waiting: bool,
+    /// Running average of how long a batch takes, in milliseconds
+    latency: Option<f64>,
+}
+
+/// One batch of names currently being fetched
+struct InFlight<H, T> {
+    id: usize,
+    names: Vec<T>,
+    handle: H,
+    /// When the request was sent, in milliseconds
+    started: f64,
+}
+
+/// A batch of names to send in one request (or for a single name's page, just
+/// one lookup)
+#[derive(Debug)]
+pub struct Batch<H, T = Name> {
+    pub id: usize,
+    pub names: Vec<T>,
+    pub handle: H,
+    /// Milliseconds to wait for a response
+    pub timeout: u32,
+}
+
+/// What the caller needs to do after a request has finished.
+#[derive(Debug, PartialEq)]
+pub enum Outcome<T = Name> {
+    /// The request succeeded, so its results can be stored.
+    Done,
+    /// The names have gone back on the queue, to be retried.
+    Requeued,
+    /// The names have gone back on the queue, but nothing more should be sent
+    /// for this many milliseconds, after which the caller should call
+    /// [Queue::end_backoff].
+    BackOff(u32),
+    /// These names (the batch, and possibly everything else queued) failed,
+    /// and should be reported.
+    Failed(Vec<T>),
+}
+
+// We can't just derive these due to
+// https://github.com/rust-lang/rust/issues/26925
+impl<H, D> Default for Queue<H, D> {
+    fn default() -> Self {
+        Queue {
+            wanted: HashSet::new(),
+            wanted_from: None,
+            needs_pruning: false,
+            paused: false,
+            offline: false,
+            timeout: 0,
+            visible: HashSet::new(),
+            gender: Scheduler::default(),
+            country: Scheduler::default(),
+            detail: Scheduler { batch_size: 1, max_batch: 1, ..Scheduler::default() },
+            next_id: 0,
+            saved: 0,
+        }
+    }
+}
+
+impl<H, T> Default for Scheduler<H, T> {
+    fn default() -> Self {
+        Scheduler {
+            pending: VecDeque::new(),
+            in_flight: Vec::new(),
+            batch_size: API_CHUNKS,
+            max_batch: API_CHUNKS,
+            backoff: None,
+            waiting: false,
+            latency: None,
+        }
+    }
+}
+
+impl<H, T> Scheduler<H, T> {
+    /// Number of names waiting to be sent
+    pub fn queued(&self) -> usize { self.pending.len() }
+
+    /// Number of names currently being fetched
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.iter().map(|b| b.names.len()).sum()
+    }
+
+    /// Whether we are waiting before retrying, since the API said we were
+    /// sending too many requests
+    pub fn backing_off(&self) -> bool { self.waiting }
+
+    /// Estimated milliseconds until everything queued or in flight has been
+    /// fetched, based on the batch latency seen so far (so [None] until the
+    /// first batch comes back).
+    pub fn eta(&self) -> Option<f64> {
+        let latency = self.latency?;
+        let names = self.queued() + self.in_flight();
+        let batches = (names as f64 / self.batch_size as f64).ceil();
+        let rounds = (batches / MAX_CONCURRENT as f64).ceil();
+        Some(rounds * latency)
+    }
+
+    /// Take the next batch of names to send, if now is the time to send one.
+    /// We only send a partial batch when nothing else is in flight, since
+    /// otherwise more names may well turn up to fill it by the time one
+    /// finishes.
+    fn next_batch(&mut self) -> Option<Vec<T>> {
+        if self.waiting || self.in_flight.len() >= MAX_CONCURRENT {
+            return None;
+        }
+        let len = self.pending.len();
+        if len == 0 || (len < self.batch_size && !self.in_flight.is_empty()) {
+            return None;
+        }
+        Some(self.pending.drain(..len.min(self.batch_size)).collect())
+    }
+
+    /// Record a batch as finished, returning how long it took, in
+    /// milliseconds.
+    fn finish(&mut self, id: usize, now: f64) -> f64 {
+        let started =
+            self.in_flight.iter().find(|batch| batch.id == id).map_or(now, |b| b.started);
+        self.in_flight.retain(|batch| batch.id != id);
+        now - started
+    }
+
+    /// Put names back at the front of the queue, to be retried first.
+    fn requeue(&mut self, names: Vec<T>) {
+        for name in names.into_iter().rev() {
+            self.pending.push_front(name);
+        }
+    }
+
+    /// Start waiting before sending anything more, returning how long for.
+    fn back_off(&mut self) -> u32 {
+        let delay =
+            self.backoff.map_or(INITIAL_BACKOFF, |delay| (delay * 2).min(MAX_BACKOFF));
+        self.backoff = Some(delay);
+        self.waiting = true;
+        delay
+    }
+
+    /// Note a successful request, and how long it took.
+    fn succeeded(&mut self, latency: f64) {
+        self.latency = Some(match self.latency {
+            Some(average) => average + LATENCY_SMOOTHING * (latency - average),
+            None => latency,
+        });
+        self.backoff = None;
+        self.batch_size = (self.batch_size * 2).min(self.max_batch);
+    }
+
+    /// Retry a batch containing an invalid name as two halves, so that after
+    /// enough splitting, the invalid name ends up in a batch of its own.
+    fn split(&mut self, names: Vec<T>) {
+        self.batch_size = (names.len() / 2).max(1);
+        self.requeue(names);
+    }
+
+    /// Note a failed request.
+    fn failed(&mut self) { self.batch_size = (self.batch_size / 2).max(1); }
+
+    /// Take the next batch to send, if any, recording it as in-flight from time
+    /// `now`, with a fresh handle from `new_handle`.
+    fn start(
+        &mut self,
+        id: usize,
+        now: f64,
+        timeout: u32,
+        new_handle: impl FnOnce() -> H,
+    ) -> Option<Batch<H, T>>
+    where
+        H: Clone,
+        T: Clone,
+    {
+        let names = self.next_batch()?;
+        let handle = new_handle();
+        self.in_flight.push(InFlight {
+            id,
+            names: names.clone(),
+            handle: handle.clone(),
+            started: now,
+        });
+        Some(Batch { id, names, handle, timeout })
+    }
+
+    /// Record a batch as finished, as for [Queue::finished].
+    fn finished(
+        &mut self,
+        batch: Batch<H, T>,
+        error: Option<&ApiError>,
+        now: f64,
+        online: bool,
+    ) -> Outcome<T> {
+        let names = batch.names;
+        let latency = self.finish(batch.id, now);
+        match error {
+            // Success!
+            None => {
+                self.succeeded(latency);
+                Outcome::Done
+            }
+
+            // Too many requests: wait a while, then try again, unless we've
+            // already waited as long as we're willing to.
+            Some(ApiError::LimitExceeded)
+                if self.waiting || self.backoff < Some(MAX_BACKOFF) =>
+            {
+                self.requeue(names);
+                if self.waiting {
+                    Outcome::Requeued
+                } else {
+                    Outcome::BackOff(self.back_off())
+                }
+            }
+
+            // The connection has gone: try again once it's back.
+            Some(ApiError::Http(_) | ApiError::Timeout) if !online => {
+                self.requeue(names);
+                Outcome::Requeued
+            }
+            // One of several names is invalid, but we don't know which: split
+            // the batch up until we find out.
+            Some(ApiError::InvalidName(_)) if names.len() > 1 => {
+                self.split(names);
+                Outcome::Requeued
+            }
+            // Failure
+            Some(err) => {
+                // Note everything that was waiting on this. If we've given up
+                // waiting for the API to accept more requests, that's
+                // everything still queued too.
+                let mut failed = names;
+                if matches!(err, ApiError::LimitExceeded) {
+                    self.backoff = None;
+                    failed.extend(self.pending.drain(..));
+                }
+                // An invalid name is a problem with that name, rather than the
+                // request.
+                if !matches!(err, ApiError::InvalidName(_)) {
+                    self.failed();
+                }
+                Outcome::Failed(failed)
+            }
+        }
+    }
+}
+
+impl<H, D> Queue<H, D> {
+    /// The scheduler for one service
+    pub fn scheduler(&self, service: Service) -> &Scheduler<H> {
+        match service {
+            Service::Gender => &self.gender,
+            Service::Country => &self.country,
+        }
+    }
+
+    /// The scheduler for one service, mutably
+    fn scheduler_mut(&mut self, service: Service) -> &mut Scheduler<H> {
+        match service {
+            Service::Gender => &mut self.gender,
+            Service::Country => &mut self.country,
+        }
+    }
+
+    /// Whether the user has paused sending any more requests
+    pub fn paused(&self) -> bool { self.paused }
+
+    /// Pause or resume sending requests. Anything already in flight carries on
+    /// regardless.
+    pub fn set_paused(&mut self, paused: bool) { self.paused = paused; }
+
+    /// Whether we are holding off until the network connection is back
+    pub fn offline(&self) -> bool { self.offline }
+
+    /// Note whether the network connection is available.
+    pub fn set_offline(&mut self, offline: bool) { self.offline = offline; }
+
+    /// Set how long to wait for each request, in seconds.
+    pub fn set_timeout(&mut self, secs: u32) { self.timeout = secs * 1000; }
+
+    /// Whether a name is currently wanted
+    pub fn is_wanted(&self, name: &Name) -> bool { self.wanted.contains(name) }
+
+    /// Update the names currently wanted, cancelling (via `cancel`) any
+    /// in-flight requests that are no longer for any of them.
+    pub fn set_wanted(&mut self, names: Rc<Names>, mut cancel: impl FnMut(&H)) {
+        if matches!(&self.wanted_from, Some(from) if Rc::ptr_eq(from, &names)) {
+            return;
+        }
+        self.wanted = names.iter().cloned().collect();
+        self.wanted_from = Some(names);
+        self.needs_pruning = true;
+        for batch in self.gender.in_flight.iter().chain(&self.country.in_flight) {
+            if !batch.names.iter().any(|name| self.wanted.contains(name)) {
+                cancel(&batch.handle);
+            }
+        }
+    }
+
+    /// Queue a name to be looked up by one service.
+    pub fn enqueue(&mut self, service: Service, name: Name) {
+        self.scheduler_mut(service).pending.push_back(name);
+    }
+
+    /// Update the names currently on screen, moving any that are queued to the
+    /// front of the queue.
+    pub fn set_visible(&mut self, names: &[Name]) {
+        let visible: HashSet<Name> = names.iter().cloned().collect();
+        if visible != self.visible {
+            self.visible = visible;
+            self.prioritise();
+        }
+    }
+
+    /// Move queued names that are on screen to the front of the queue, leaving
+    /// everything else in order behind them.
+    pub fn prioritise(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
+        for scheduler in [&mut self.gender, &mut self.country] {
+            let (mut pending, rest): (VecDeque<_>, VecDeque<_>) =
+                scheduler.pending.drain(..).partition(|name| self.visible.contains(name));
+            pending.extend(rest);
+            scheduler.pending = pending;
+        }
+    }
+
+    /// Drop any queued names that have since been deleted, returning them.
+    pub fn prune(&mut self) -> Vec<Name> {
+        let mut dropped = Vec::new();
+        if !self.needs_pruning {
+            return dropped;
+        }
+        self.needs_pruning = false;
+        for scheduler in [&mut self.gender, &mut self.country] {
+            scheduler.pending.retain(|name| {
+                let wanted = self.wanted.contains(name);
+                if !wanted {
+                    dropped.push(name.clone());
+                }
+                wanted
+            });
+        }
+        self.saved += dropped.len();
+        dropped
+    }
+
+    /// Take the next batch to send for a service, if any, recording it as
+    /// in-flight from time `now`, with a fresh handle from `new_handle`.
+    pub fn start_batch(
+        &mut self,
+        service: Service,
+        now: f64,
+        new_handle: impl FnOnce() -> H,
+    ) -> Option<Batch<H>>
+    where
+        H: Clone,
+    {
+        if self.paused || self.offline {
+            return None;
+        }
+        let (id, timeout) = (self.next_id, self.timeout);
+        let batch = self.scheduler_mut(service).start(id, now, timeout, new_handle)?;
+        self.next_id += 1;
+        Some(batch)
+    }
+
+    /// Take the next lookup to send for a single name's page, if any, as for
+    /// [Queue::start_batch].
+    pub fn start_detail(
+        &mut self,
+        now: f64,
+        new_handle: impl FnOnce() -> H,
+    ) -> Option<Batch<H, D>>
+    where
+        H: Clone,
+        D: Clone,
+    {
+        if self.paused || self.offline {
+            return None;
+        }
+        let batch = self.detail.start(self.next_id, now, self.timeout, new_handle)?;
+        self.next_id += 1;
+        Some(batch)
+    }
+
+    /// Forget a batch that was cancelled, since none of its names are wanted
+    /// any more.
+    pub fn cancelled(&mut self, service: Service, id: usize, now: f64) {
+        self.scheduler_mut(service).finish(id, now);
+    }
+
+    /// Record a batch as finished at time `now`, with any error, and work out
+    /// what happens next. `online` is whether the network connection still
+    /// seems to be there, so connection failures can be retried once it's
+    /// back.
+    pub fn finished(
+        &mut self,
+        service: Service,
+        batch: Batch<H>,
+        error: Option<&ApiError>,
+        now: f64,
+        online: bool,
+    ) -> Outcome {
+        self.note_connection(error, online);
+        self.scheduler_mut(service).finished(batch, error, now, online)
+    }
+
+    /// Record a lookup for a single name's page as finished, as for
+    /// [Queue::finished].
+    pub fn finished_detail(
+        &mut self,
+        batch: Batch<H, D>,
+        error: Option<&ApiError>,
+        now: f64,
+        online: bool,
+    ) -> Outcome<D> {
+        self.note_connection(error, online);
+        self.detail.finished(batch, error, now, online)
+    }
+
+    /// Internal helper function: hold off sending anything more if a request
+    /// failed since the connection has gone.
+    fn note_connection(&mut self, error: Option<&ApiError>, online: bool) {
+        if !online && matches!(error, Some(ApiError::Http(_) | ApiError::Timeout)) {
+            self.offline = true;
+        }
+    }
+
+    /// Stop waiting out a backoff, so requests can be sent again.
+    pub fn end_backoff(&mut self, service: Service) {
+        self.scheduler_mut(service).waiting = false;
+    }
+
+    /// Queue a lookup for the page for a single name.
+    pub fn enqueue_detail(&mut self, lookup: D) { self.detail.pending.push_back(lookup); }
+
+    /// Stop waiting out a backoff for the lookups for single names' pages.
+    pub fn end_detail_backoff(&mut self) { self.detail.waiting = false; }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Queue with plain numbers as handles, so tests can see what's cancelled
+    type TestQueue = Queue<usize>;
+
+    /// Internal helper function: names from strings.
+    fn names(names: &[&str]) -> Vec<Name> {
+        names.iter().map(|&name| Name::from_ref(name)).collect()
+    }
+
+    /// Internal helper function: a queue with these names wanted and queued
+    /// for the gender service.
+    fn queued(wanted: &[&str]) -> TestQueue {
+        let mut queue = TestQueue::default();
+        queue.set_wanted(Rc::new(names(wanted)), |_| {});
+        for name in names(wanted) {
+            queue.enqueue(Service::Gender, name);
+        }
+        queue
+    }
+
+    /// Internal helper function: start the next gender batch, with its id as
+    /// its handle.
+    fn start(queue: &mut TestQueue) -> Option<Batch<usize>> {
+        let id = queue.next_id;
+        queue.start_batch(Service::Gender, 0.0, || id)
+    }
+
+    /// Internal helper function: finish a gender batch with any error.
+    fn finish(
+        queue: &mut TestQueue,
+        batch: Batch<usize>,
+        error: Option<ApiError>,
+    ) -> Outcome {
+        queue.finished(Service::Gender, batch, error.as_ref(), 100.0, true)
+    }
+
+    #[test]
+    fn prune() {
+        let mut queue = queued(&["Anna", "Jean", "Kim"]);
+        queue.enqueue(Service::Country, Name::from_ref("Jean"));
+        queue.set_wanted(Rc::new(names(&["Anna"])), |_| {});
+        assert_eq!(queue.prune(), names(&["Jean", "Kim", "Jean"]));
+        assert_eq!(queue.saved, 3);
+        assert_eq!(queue.scheduler(Service::Gender).queued(), 1);
+        assert_eq!(queue.scheduler(Service::Country).queued(), 0);
+
+        // Nothing more to do until the wanted names change again
+        queue.enqueue(Service::Gender, Name::from_ref("Peter"));
+        assert_eq!(queue.prune(), names(&[]));
+    }
+
+    #[test]
+    fn set_wanted() {
+        let mut queue = queued(&["Anna", "Jean"]);
+        let batch = start(&mut queue).unwrap();
+        let mut cancelled = Vec::new();
+
+        // Still wanted in part
+        queue.set_wanted(Rc::new(names(&["Jean", "Kim"])), |&h| cancelled.push(h));
+        assert!(cancelled.is_empty());
+        assert!(queue.is_wanted(&Name::from_ref("Kim")));
+        assert!(!queue.is_wanted(&Name::from_ref("Anna")));
+
+        // Not wanted at all, but only cancelled when the list actually changes
+        let list = Rc::new(names(&["Kim"]));
+        queue.set_wanted(list.clone(), |&h| cancelled.push(h));
+        queue.set_wanted(list, |&h| cancelled.push(h));
+        assert_eq!(cancelled, [batch.handle]);
+    }
+
+    #[test]
+    fn prioritise() {
+        let mut queue = queued(&["Anna", "Jean", "Kim", "Peter"]);
+        queue.set_visible(&names(&["Kim", "Peter"]));
+        assert_eq!(
+            start(&mut queue).unwrap().names,
+            names(&["Kim", "Peter", "Anna", "Jean"])
+        );
+
+        // Names queued later wait their turn until re-prioritised
+        let mut queue = queued(&["Anna", "Jean"]);
+        queue.set_visible(&names(&["Kim"]));
+        queue.enqueue(Service::Gender, Name::from_ref("Kim"));
+        queue.prioritise();
+        assert_eq!(start(&mut queue).unwrap().names, names(&["Kim", "Anna", "Jean"]));
+    }
+
+    #[test]
+    fn set_visible() {
+        let mut queue = queued(&["Anna", "Jean", "Kim"]);
+        queue.set_visible(&names(&["Kim"]));
+        queue.enqueue(Service::Gender, Name::from_ref("Kim"));
+
+        // The same names again don't reorder anything.
+        queue.set_visible(&names(&["Kim"]));
+        assert_eq!(
+            start(&mut queue).unwrap().names,
+            names(&["Kim", "Anna", "Jean", "Kim"])
+        );
+    }
+
+    #[test]
+    fn backoff() {
+        let mut queue = queued(&["Anna", "Jean"]);
+        let mut delays = Vec::new();
+        loop {
+            let batch = start(&mut queue).unwrap();
+            match finish(&mut queue, batch, Some(ApiError::LimitExceeded)) {
+                Outcome::BackOff(delay) => {
+                    assert!(queue.scheduler(Service::Gender).backing_off());
+                    assert!(start(&mut queue).is_none());
+                    queue.end_backoff(Service::Gender);
+                    delays.push(delay);
+                }
+                outcome => {
+                    // Given up: everything fails.
+                    assert_eq!(outcome, Outcome::Failed(names(&["Anna", "Jean"])));
+                    break;
+                }
+            }
+        }
+        assert_eq!(delays, [2_000, 4_000, 8_000, 16_000, 32_000]);
+
+        // A success resets the backoff.
+        let mut queue = queued(&["Anna", "Jean"]);
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(
+            finish(&mut queue, batch, Some(ApiError::LimitExceeded)),
+            Outcome::BackOff(2_000)
+        );
+        queue.end_backoff(Service::Gender);
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(finish(&mut queue, batch, None), Outcome::Done);
+        queue.enqueue(Service::Gender, Name::from_ref("Kim"));
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(
+            finish(&mut queue, batch, Some(ApiError::LimitExceeded)),
+            Outcome::BackOff(2_000)
+        );
+    }
+
+    #[test]
+    fn backoff_while_waiting() {
+        let wanted: Vec<_> = (0..2 * API_CHUNKS).map(|i| format!("Name{i}")).collect();
+        let wanted: Vec<_> = wanted.iter().map(String::as_str).collect();
+        let mut queue = queued(&wanted);
+        let first = start(&mut queue).unwrap();
+        let second = start(&mut queue).unwrap();
+
+        // Both requests were sent before the backoff, so only one starts it.
+        let limit = || Some(ApiError::LimitExceeded);
+        assert_eq!(finish(&mut queue, first, limit()), Outcome::BackOff(2_000));
+        assert_eq!(finish(&mut queue, second, limit()), Outcome::Requeued);
+        assert_eq!(queue.scheduler(Service::Gender).queued(), 2 * API_CHUNKS);
+    }
+
+    #[test]
+    fn finished() {
+        let mut queue = queued(&["Anna", "Jean", "Kim", "Peter"]);
+        let invalid = || Some(ApiError::InvalidName("Invalid name".to_owned()));
+
+        // Split the batch until the invalid name is on its own.
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(finish(&mut queue, batch, invalid()), Outcome::Requeued);
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(batch.names, names(&["Anna", "Jean"]));
+        assert_eq!(finish(&mut queue, batch, invalid()), Outcome::Requeued);
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(batch.names, names(&["Anna"]));
+        assert_eq!(finish(&mut queue, batch, None), Outcome::Done);
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(batch.names, names(&["Jean", "Kim"]));
+        assert_eq!(finish(&mut queue, batch, invalid()), Outcome::Requeued);
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(batch.names, names(&["Jean"]));
+        assert_eq!(
+            finish(&mut queue, batch, invalid()),
+            Outcome::Failed(names(&["Jean"]))
+        );
+
+        // The rest carry on.
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(batch.names, names(&["Kim"]));
+        assert_eq!(finish(&mut queue, batch, None), Outcome::Done);
+
+        // Other failures are reported straight away, unless the connection has
+        // gone, in which case everything waits for it to come back.
+        let batch = start(&mut queue).unwrap();
+        assert_eq!(
+            finish(&mut queue, batch, Some(ApiError::Timeout)),
+            Outcome::Failed(names(&["Peter"]))
+        );
+        let mut queue = queued(&["Anna", "Jean", "Kim"]);
+        let batch = start(&mut queue).unwrap();
+        let outcome =
+            queue.finished(Service::Gender, batch, Some(&ApiError::Timeout), 0.0, false);
+        assert_eq!(outcome, Outcome::Requeued);
+        assert!(queue.offline());
+        assert!(start(&mut queue).is_none());
+    }
+}
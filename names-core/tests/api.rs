@@ -0,0 +1,34 @@
+//! Gender results: confidence intervals, and summaries.
+
+use names_core::api::{Gender, GenderResult};
+
+/// Internal helper function: a gender result.
+fn result(gender: Option<Gender>, probability: f32, count: u32) -> GenderResult {
+    GenderResult { gender, probability, count, offline: false }
+}
+
+#[test]
+fn intervals() {
+    // Narrower, the more people there are behind the probability
+    let (low, high) = result(Some(Gender::Female), 0.9, 10).interval().unwrap();
+    assert!((low - 0.596).abs() < 0.001 && (high - 0.982).abs() < 0.001, "{low} {high}");
+    let (low, high) = result(Some(Gender::Female), 0.9, 10_000).interval().unwrap();
+    assert!((low - 0.894).abs() < 0.001 && (high - 0.906).abs() < 0.001, "{low} {high}");
+
+    // Always within 0 to 1, and containing the probability itself
+    let (low, high) = result(Some(Gender::Male), 1.0, 3).interval().unwrap();
+    assert!(low > 0.0 && high == 1.0, "{low} {high}");
+
+    // No interval without any data
+    assert_eq!(result(None, 0.0, 0).interval(), None);
+    assert_eq!(result(Some(Gender::Male), 0.5, 0).interval(), None);
+}
+
+#[test]
+fn summaries() {
+    for (probability, expected) in [(0.99, "F"), (0.8, "F?"), (0.6, "F??")] {
+        assert_eq!(result(Some(Gender::Female), probability, 100).summarised(), expected);
+    }
+    assert_eq!(result(Some(Gender::Male), 0.85, 100).summarised(), "M");
+    assert_eq!(result(None, 0.0, 0).summarised(), "?");
+}
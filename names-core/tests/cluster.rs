@@ -0,0 +1,158 @@
+//! Grouping spelling variants of names, and combining their results.
+
+use names_core::api::{CountryResult, Gender, GenderResult};
+use names_core::cluster::*;
+use names_core::Name;
+use proptest::prelude::*;
+
+/// Internal helper function: names from strings.
+fn names(names: &[&str]) -> Vec<Name> {
+    names.iter().map(|&name| Name::from_ref(name)).collect()
+}
+
+#[test]
+fn variants() {
+    for group in [
+        &["Mohammed", "Muhammad", "Mohamed", "Mohammad"][..],
+        &["Catherine", "Katherine", "Kathryn", "Katharine"],
+        &["Stephen", "Steven"],
+        &["Sara", "Sarah"],
+        &["Jon", "John"],
+        &["Philip", "Phillip", "Filip"],
+    ] {
+        for a in group {
+            for b in group {
+                assert!(similar(a, b), "{a} and {b}");
+            }
+        }
+    }
+    for (a, b) in
+        [("Anna", "Peter"), ("Ian", "Ann"), ("Mohammed", "Mahmoud"), ("Mark", "Mary")]
+    {
+        assert!(!similar(a, b), "{a} and {b}");
+    }
+}
+
+#[test]
+fn grouping() {
+    let list =
+        names(&["Mohammed", "Anna", "Kathryn", "muhammad", "Mohammed", "Katherine"]);
+    assert_eq!(
+        clusters(&list, &[]),
+        [
+            names(&["Mohammed", "muhammad"]),
+            names(&["Anna"]),
+            names(&["Kathryn", "Katherine"])
+        ]
+    );
+}
+
+#[test]
+fn edits() {
+    let list = names(&["Mohammed", "Anna", "Muhammad", "Ann"]);
+    let merge = Edit::Merge(Name::from_static("anna"), Name::from_static("Ann"));
+    let split = Edit::Split(Name::from_static("Muhammad"));
+    assert_eq!(
+        clusters(&list, &[merge.clone(), split.clone()]),
+        [names(&["Mohammed"]), names(&["Anna", "Ann"]), names(&["Muhammad"])]
+    );
+
+    // Later edits take precedence, and names not in the list are ignored.
+    let rejoin =
+        Edit::Merge(Name::from_static("Mohammed"), Name::from_static("Muhammad"));
+    let unknown = Edit::Split(Name::from_static("Xyzzy"));
+    assert_eq!(
+        clusters(&list, &[split, rejoin, merge, unknown]),
+        [names(&["Mohammed", "Muhammad"]), names(&["Anna", "Ann"])]
+    );
+}
+
+#[test]
+fn distances() {
+    assert_eq!(edit_distance("Mohammed", "Mohamed"), 1);
+    assert_eq!(edit_distance("kitten", "SITTING"), 3);
+    assert_eq!(edit_distance("", "Anna"), 4);
+}
+
+/// Internal helper function: a gender result.
+fn gender(gender: Option<Gender>, probability: f32, count: u32) -> GenderResult {
+    GenderResult { gender, probability, count, offline: false }
+}
+
+#[test]
+fn combined_genders() {
+    assert_eq!(combine_genders([]), None);
+
+    // 900 of 1000 Mohammeds, and 100 of 100 Muhammads, are male.
+    let results =
+        [gender(Some(Gender::Male), 0.9, 1000), gender(Some(Gender::Male), 1.0, 100)];
+    let combined = combine_genders(&results).unwrap();
+    assert_eq!((combined.gender, combined.count), (Some(Gender::Male), 1100));
+    assert!((combined.probability - 1000.0 / 1100.0).abs() < 1e-6);
+
+    let results =
+        [gender(Some(Gender::Female), 0.8, 10), gender(Some(Gender::Male), 0.8, 30)];
+    let combined = combine_genders(&results).unwrap();
+    assert_eq!(combined.gender, Some(Gender::Male));
+    assert!((combined.probability - 0.65).abs() < 1e-6);
+
+    let results = [gender(None, 0.0, 0)];
+    assert_eq!(combine_genders(&results), Some(gender(None, 0.0, 0)));
+}
+
+#[test]
+fn combined_countries() {
+    let country = |country: &str, probability| CountryResult {
+        country: country.to_owned(),
+        probability,
+    };
+    let (a, b) = ([country("FR", 0.5), country("DE", 0.3)], [country("DE", 1.0)]);
+    let combined = combine_countries([(&a[..], 300), (&b[..], 100)]);
+    let countries: Vec<_> = combined.iter().map(|c| c.country.as_str()).collect();
+    assert_eq!(countries, ["DE", "FR"]);
+    assert!((combined[0].probability - 0.475).abs() < 1e-6);
+    assert!((combined[1].probability - 0.375).abs() < 1e-6);
+
+    // Equal weights, if there are no counts
+    let combined = combine_countries([(&a[..], 0), (&b[..], 0)]);
+    assert_eq!(combined[0].country, "DE");
+    assert!((combined[0].probability - 0.65).abs() < 1e-6);
+}
+
+proptest! {
+    #[test]
+    fn partition(
+        list in prop::collection::vec("[A-Za-z]{1,8}", 0..30),
+        splits in prop::collection::vec(any::<prop::sample::Index>(), 0..5),
+    ) {
+        let list: Vec<Name> = list.iter().map(|name| Name::from_ref(name)).collect();
+        let edits: Vec<_> = match list.is_empty() {
+            true => Vec::new(),
+            false => splits.iter().map(|i| Edit::Split(i.get(&list).clone())).collect(),
+        };
+        let grouped: Vec<String> =
+            clusters(&list, &edits).concat().iter().map(|n| n.to_ascii_lowercase()).collect();
+        let mut distinct: Vec<String> = Vec::new();
+        for name in &list {
+            if !distinct.contains(&name.to_ascii_lowercase()) {
+                distinct.push(name.to_ascii_lowercase());
+            }
+        }
+        let mut sorted = grouped.clone();
+        sorted.sort();
+        distinct.sort();
+        prop_assert_eq!(sorted, distinct);
+    }
+
+    #[test]
+    fn distance_is_a_metric(a in "[a-z]{0,8}", b in "[a-z]{0,8}", c in "[a-z]{0,8}") {
+        prop_assert_eq!(edit_distance(&a, &b), edit_distance(&b, &a));
+        prop_assert_eq!(edit_distance(&a, &a), 0);
+        prop_assert!(edit_distance(&a, &c) <= edit_distance(&a, &b) + edit_distance(&b, &c));
+    }
+
+    #[test]
+    fn keys_ignore_case(name in "[A-Za-z]{1,12}") {
+        prop_assert_eq!(phonetic_key(&name), phonetic_key(&name.to_ascii_uppercase()));
+    }
+}
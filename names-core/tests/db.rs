@@ -0,0 +1,81 @@
+//! In-memory cache of API results.
+
+use names_core::api::{Gender, GenderResult, Service};
+use names_core::db::*;
+use names_core::queue::Queue;
+use names_core::{AllResults, Name, Remote};
+
+/// Internal helper function: names from strings.
+fn names(names: &[&str]) -> Vec<Name> {
+    names.iter().map(|&name| Name::from_ref(name)).collect()
+}
+
+/// Internal helper function: a gender result.
+fn gender(offline: bool) -> GenderResult {
+    GenderResult { gender: Some(Gender::Female), probability: 0.9, count: 100, offline }
+}
+
+/// Internal helper function: the gender results for one name.
+fn genders(db: &Db, name: &str) -> Option<Remote<GenderResult>> {
+    db.get(&Name::from_ref(name)).map(|results| results.gender.clone())
+}
+
+#[test]
+fn insert_new_names() {
+    let mut db = Db::new();
+    assert_eq!(insert_new(&mut db, &names(&["Anna", "Jean"])), names(&["Anna", "Jean"]));
+    assert_eq!(insert_new(&mut db, &names(&["Jean", "Kim"])), names(&["Kim"]));
+    assert_eq!(genders(&db, "Kim"), Some(Remote::Loading));
+}
+
+#[test]
+fn store_and_fail() {
+    let mut db = Db::new();
+    insert_new(&mut db, &names(&["Anna", "Jean"]));
+    let results = vec![
+        (Name::from_ref("Anna"), gender(false)),
+        (Name::from_ref("Kim"), gender(false)),
+    ];
+    store(&mut db, results, |results| &mut results.gender);
+    fail(&mut db, &names(&["Jean", "Peter"]), |results| &mut results.gender);
+
+    // Names no longer cached stay that way.
+    assert_eq!(genders(&db, "Anna"), Some(Remote::Success(gender(false))));
+    assert_eq!(genders(&db, "Jean"), Some(Remote::Error));
+    assert_eq!(genders(&db, "Kim"), None);
+    assert_eq!(genders(&db, "Peter"), None);
+}
+
+#[test]
+fn reset() {
+    let mut db = Db::new();
+    for (name, gender) in [
+        ("Anna", Remote::Success(gender(false))),
+        ("Jean", Remote::Success(gender(true))),
+        ("Kim", Remote::Error),
+        ("Peter", Remote::Loading),
+    ] {
+        db.insert(Name::from_ref(name), AllResults { gender, country: Remote::Error });
+    }
+    let all = names(&["Anna", "Jean", "Kim", "Peter", "Zoë"]);
+
+    // Offline estimates are looked up again, as well as failures.
+    assert_eq!(reset_failed(&mut db, Service::Gender, &all), names(&["Jean", "Kim"]));
+    assert_eq!(genders(&db, "Jean"), Some(Remote::Loading));
+    assert_eq!(reset_failed(&mut db, Service::Gender, &all), names(&[]));
+    assert_eq!(
+        reset_failed(&mut db, Service::Country, &all),
+        names(&["Anna", "Jean", "Kim", "Peter"])
+    );
+}
+
+#[test]
+fn forget() {
+    let mut db = Db::new();
+    insert_new(&mut db, &names(&["Anna", "Jean", "Kim"]));
+    let mut queue = Queue::<()>::default();
+    queue.set_wanted(std::rc::Rc::new(names(&["Anna"])), |_| {});
+    forget_unwanted(&mut db, &queue, &names(&["Anna", "Jean"]));
+    assert_eq!(db.len(), 2);
+    assert!(genders(&db, "Jean").is_none());
+}
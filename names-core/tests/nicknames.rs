@@ -0,0 +1,48 @@
+//! Nickname lookups, and choosing between a nickname's results and its full
+//! form's.
+
+use names_core::api::{Gender, GenderResult};
+use names_core::nicknames::{full_form, prefer_full_form, LOW_COUNT};
+use names_core::Remote;
+
+#[test]
+fn full_forms() {
+    for (name, expected) in [
+        ("Bob", Some("Robert")),
+        ("liz", Some("Elizabeth")),
+        ("SASHA", Some("Alexander")),
+        ("Kate", Some("Katherine")),
+        ("Fred", Some("Frederick")),
+        ("Alfie", Some("Alfred")),
+        ("Robert", None),
+        ("Xyzzy", None),
+        ("", None),
+    ] {
+        assert_eq!(full_form(name), expected, "{name:?}");
+    }
+}
+
+/// Internal helper function: a gender result from this many people.
+fn result(count: u32) -> Remote<GenderResult> {
+    Remote::Success(GenderResult {
+        gender: Some(Gender::Female),
+        probability: 0.9,
+        count,
+        offline: false,
+    })
+}
+
+#[test]
+fn preference() {
+    assert!(prefer_full_form(&result(10), &result(1000)));
+    assert!(!prefer_full_form(&result(1000), &result(10)));
+    // A nickname with plenty of data of its own keeps it, however much more
+    // the full form has.
+    assert!(!prefer_full_form(&result(LOW_COUNT), &result(100_000)));
+    assert!(prefer_full_form(&result(LOW_COUNT - 1), &result(100_000)));
+    assert!(!prefer_full_form(&result(10), &result(10)));
+    assert!(prefer_full_form(&Remote::Error, &result(10)));
+    assert!(!prefer_full_form(&Remote::Loading, &result(1000)));
+    assert!(!prefer_full_form(&result(10), &Remote::Loading));
+    assert!(!prefer_full_form(&result(10), &Remote::Error));
+}
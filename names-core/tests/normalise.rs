@@ -0,0 +1,119 @@
+//! Property tests for the name normalisation rules documented in
+//! [names_core::normalise], plus a few examples of real-world paste.
+
+use names_core::normalise::{normalise_name, parse_line, split_lines, IGNORED_NAME};
+use proptest::prelude::*;
+
+/// Invisible formatting characters, as found in text pasted from web pages and
+/// word processors
+const INVISIBLE: [char; 6] =
+    ['\u{200B}', '\u{200C}', '\u{200D}', '\u{FEFF}', '\u{00AD}', '\u{2060}'];
+
+/// Internal helper function: text of the kind people paste, with a bias
+/// towards the awkward parts of Unicode.
+fn pasted() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<String>(),
+        "[ \t\r\n]{0,3}[A-Za-z]{0,8}[ ,.'-]{0,2}[A-Za-z]{0,8}[ \t\r\n]{0,3}",
+        "[\\p{Cyrillic}\\p{Greek}\\p{Han}\\p{Arabic}A-Za-z ]{0,12}",
+        "[\u{1F300}-\u{1FAFF}\u{200B}-\u{200F}\u{FEFF}0-9 A-Za-z]{0,12}",
+    ]
+}
+
+proptest! {
+    #[test]
+    fn names_are_ascii_words(line in pasted()) {
+        if let Some(name) = parse_line(&line) {
+            prop_assert!(!name.is_empty());
+            prop_assert!(name.chars().all(|c| c.is_ascii_alphabetic()), "{:?}", name);
+            prop_assert_ne!(name.as_str(), IGNORED_NAME);
+        }
+    }
+
+    #[test]
+    fn rejected_without_letters(
+        line in "[0-9 \t.,;:!?()\u{1F300}-\u{1FAFF}\u{200B}-\u{200F}]{0,12}",
+    ) {
+        prop_assert_eq!(parse_line(&line), None);
+    }
+
+    #[test]
+    fn accepted_with_letters(line in pasted(), letter in "[A-Za-z]") {
+        let line = format!("{line} {letter}");
+        prop_assert!(normalise_name(&line).is_some());
+    }
+
+    #[test]
+    fn idempotent(line in pasted()) {
+        if let Some(name) = normalise_name(&line) {
+            prop_assert_eq!(normalise_name(&name), Some(name));
+        }
+    }
+
+    #[test]
+    fn ascii_words_unchanged(word in "[A-Za-z]{1,20}") {
+        let name = normalise_name(&word);
+        prop_assert_eq!(name.as_deref(), Some(word.as_str()));
+    }
+
+    #[test]
+    fn surrounding_whitespace_ignored(
+        word in "[A-Za-z]{1,20}",
+        before in "[ \t\r]{0,3}",
+        after in "[ \t\r]{0,3}",
+    ) {
+        prop_assert_eq!(normalise_name(&format!("{before}{word}{after}")), normalise_name(&word));
+    }
+
+    #[test]
+    fn invisible_characters_ignored(
+        word in "[A-Za-z]{2,20}",
+        at in any::<prop::sample::Index>(),
+        invisible in prop::sample::select(&INVISIBLE[..]),
+    ) {
+        let mut padded = word.clone();
+        padded.insert(at.index(word.len() + 1), invisible);
+        prop_assert_eq!(normalise_name(&padded), normalise_name(&word));
+    }
+
+    #[test]
+    fn any_line_endings(
+        // (Not empty, since "\r" then "\n" for an empty line is just "\r\n".)
+        lines in prop::collection::vec("[^\r\n]{1,10}", 1..10),
+        endings in prop::collection::vec(prop::sample::select(vec!["\n", "\r\n", "\r"]), 10),
+    ) {
+        let mut text = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                text.push_str(endings[i]);
+            }
+            text.push_str(line);
+        }
+        prop_assert_eq!(split_lines(&text).collect::<Vec<_>>(), lines);
+    }
+}
+
+#[test]
+fn examples() {
+    for (line, expected) in [
+        ("Anna", Some("Anna")),
+        ("  Peter \r", Some("Peter")),
+        ("Zoë", Some("Zoe")),
+        ("Анна", Some("Anna")),
+        ("李", Some("Li")),
+        ("Jean-Luc", Some("Jean")),
+        ("1. Jean", Some("Jean")),
+        ("😀 Kim", Some("Kim")),
+        ("Ma\u{200B}rie", Some("Marie")),
+        ("\u{FEFF}Sasha", Some("Sasha")),
+        ("First", None),
+        ("First name", None),
+        ("", None),
+        ("   ", None),
+        ("1234", None),
+        ("😀🎉", None),
+        ("\u{200B}", None),
+    ] {
+        assert_eq!(parse_line(line).as_deref(), expected, "{line:?}");
+    }
+}
@@ -0,0 +1,50 @@
+//! The offline names table: building, encoding and decoding, and lookups.
+//! (These counts are made up, just for testing.)
+
+use names_core::api::Gender;
+use names_core::offline::{Table, TableError};
+
+/// Internal helper function: a small table.
+fn table() -> Table {
+    let counts =
+        [("Anna", 980, 20), ("Andrea", 600, 400), ("Peter", 0, 1000), ("Kim", 50, 50)];
+    Table::from_counts(counts.map(|(name, f, m)| (name.to_owned(), f, m)), 10)
+}
+
+#[test]
+fn round_trip() {
+    let table = table();
+    assert_eq!(Table::decode(&table.encode()), Ok(table));
+}
+
+#[test]
+fn lookup() {
+    let table = table();
+    let anna = table.lookup("anna").unwrap();
+    assert_eq!(
+        (anna.gender, anna.probability, anna.count),
+        (Some(Gender::Female), 0.98, 1000)
+    );
+    assert!(anna.offline);
+    assert_eq!(table.lookup("PETER").unwrap().gender, Some(Gender::Male));
+    assert_eq!(table.lookup("Kim").unwrap().gender, None);
+    assert_eq!(table.lookup("Xyzzy"), None);
+}
+
+#[test]
+fn most_common_kept() {
+    let counts =
+        [("Anna", 10, 0), ("anna", 5, 1), ("Bob", 0, 12), ("Cy", 1, 1), ("Jo-Jo", 99, 0)];
+    let table = Table::from_counts(counts.map(|(name, f, m)| (name.to_owned(), f, m)), 2);
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.lookup("Anna").unwrap().count, 16);
+    assert!(table.lookup("Bob").is_some());
+    assert!(table.lookup("Cy").is_none());
+}
+
+#[test]
+fn corrupt() {
+    let bytes = table().encode();
+    assert_eq!(Table::decode(b"<!DOCTYPE html>"), Err(TableError::BadMagic));
+    assert_eq!(Table::decode(&bytes[..bytes.len() - 1]), Err(TableError::Truncated));
+}
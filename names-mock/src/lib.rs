@@ -0,0 +1,257 @@
+//! Local stand-in for the genderize.io, nationalize.io and agify.io APIs, for
+//! testing without the network (or using up any quota).
+//!
+//! Results come from fixture files in each API's own response format (see
+//! `fixtures/`, whose numbers are illustrative, rather than real API data).
+//! Names without a fixture are left out of the response, just like a partial
+//! batch. A few special names, and [MockApi::fail_next], simulate the API's
+//! other behaviour:
+//!
+//! - [INVALID_NAME] in a batch gets the whole batch rejected with a 422.
+//! - [SLOW_NAME] in a batch delays the response by [SLOW_DELAY].
+//!
+//! Gender results localised with `country_id` are the same as any others, just
+//! with the country echoed back, as the API does.
+#![warn(rust_2018_idioms, missing_docs, unused)]
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use std::{fs, io};
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Response, Server};
+
+/// Error starting the server, as reported by tiny_http
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The services we stand in for, by domain name stem
+pub const SERVICES: [&str; 3] = ["genderize", "nationalize", "agify"];
+
+/// Name that makes the whole batch it's in invalid
+pub const INVALID_NAME: &str = "Invalid";
+
+/// Name that makes the response to the batch it's in slow
+pub const SLOW_NAME: &str = "Slow";
+
+/// How long a response with [SLOW_NAME] takes
+pub const SLOW_DELAY: Duration = Duration::from_secs(3);
+
+/// Fixture results for each service, keyed by lower-case name
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures(HashMap<&'static str, HashMap<String, Value>>);
+
+/// One request the mock has received
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// Service domain name stem, eg "genderize"
+    pub service: String,
+    /// Names asked for, in order
+    pub names: Vec<String>,
+    /// API key passed along, if any
+    pub api_key: Option<String>,
+}
+
+/// A running mock API server, which stops when dropped.
+pub struct MockApi {
+    server: Arc<Server>,
+    url: String,
+    state: Arc<Mutex<State>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Internal state shared with the request handlers
+#[derive(Default)]
+struct State {
+    fixtures: Fixtures,
+    /// Statuses to fail the next requests with, in order
+    failures: VecDeque<u16>,
+    requests: Vec<Request>,
+}
+
+impl Fixtures {
+    /// The fixtures in this crate's `fixtures/` directory
+    pub fn bundled() -> Self {
+        let mut fixtures = Fixtures::default();
+        for (service, json) in [
+            ("genderize", include_str!("../fixtures/genderize.json")),
+            ("nationalize", include_str!("../fixtures/nationalize.json")),
+            ("agify", include_str!("../fixtures/agify.json")),
+        ] {
+            fixtures.add(service, json).expect("bundled fixtures are valid");
+        }
+        fixtures
+    }
+
+    /// Load fixtures from `{service}.json` files in a directory. Any missing
+    /// file just means no results for that service.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut fixtures = Fixtures::default();
+        for service in SERVICES {
+            let path = dir.join(format!("{service}.json"));
+            if path.exists() {
+                fixtures.add(service, &fs::read_to_string(&path)?)?;
+            }
+        }
+        Ok(fixtures)
+    }
+
+    /// Internal helper function: add the results for one service, from a JSON
+    /// array of results in the API's own format.
+    fn add(&mut self, service: &'static str, json: &str) -> io::Result<()> {
+        let results: Vec<Value> = serde_json::from_str(json)?;
+        let by_name = results
+            .into_iter()
+            .filter_map(|r| Some((r.get("name")?.as_str()?.to_lowercase(), r)))
+            .collect();
+        self.0.insert(service, by_name);
+        Ok(())
+    }
+
+    /// The result for one name, as if it had been asked for by that name
+    fn get(&self, service: &str, name: &str) -> Option<Value> {
+        let mut result = self.0.get(service)?.get(&name.to_lowercase())?.clone();
+        result["name"] = json!(name);
+        Some(result)
+    }
+}
+
+impl MockApi {
+    /// Start a mock server with the bundled fixtures, on a free local port.
+    pub fn start() -> Self {
+        MockApi::serve("127.0.0.1:0", Fixtures::bundled()).expect("starting mock API")
+    }
+
+    /// Start a mock server on the given address.
+    pub fn serve(addr: &str, fixtures: Fixtures) -> Result<Self, BoxError> {
+        let server = Arc::new(Server::http(addr)?);
+        let url = format!("http://{}", server.server_addr());
+        let state = Arc::new(Mutex::new(State { fixtures, ..State::default() }));
+        let thread = {
+            let (server, state) = (server.clone(), state.clone());
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    // Each on its own thread, so slow responses don't hold up
+                    // the others.
+                    let state = state.clone();
+                    thread::spawn(move || respond(&state, request));
+                }
+            })
+        };
+        Ok(MockApi { server, url, state, thread: Some(thread) })
+    }
+
+    /// Base URL to send requests to, eg `{url}/genderize?name[]=Anna`
+    pub fn url(&self) -> &str { &self.url }
+
+    /// Fail the next `count` requests with an HTTP status, such as 429 or 503,
+    /// along with the API's error message for it.
+    pub fn fail_next(&self, status: u16, count: usize) {
+        let failures = &mut self.state.lock().unwrap().failures;
+        failures.resize(failures.len() + count, status);
+    }
+
+    /// Every request received so far, oldest first
+    pub fn requests(&self) -> Vec<Request> { self.state.lock().unwrap().requests.clone() }
+}
+
+impl Drop for MockApi {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            _ = thread.join();
+        }
+    }
+}
+
+/// Internal helper function: answer one request.
+fn respond(state: &Mutex<State>, request: tiny_http::Request) {
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let (status, body) = answer(state, path.trim_matches('/'), query);
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+        .with_header(Header::from_bytes("Access-Control-Allow-Origin", "*").unwrap());
+    _ = request.respond(response);
+}
+
+/// Internal helper function: the status and JSON body for a request.
+fn answer(state: &Mutex<State>, service: &str, query: &str) -> (u16, Value) {
+    if !SERVICES.contains(&service) {
+        return error(404, "Not found");
+    }
+    let (names, api_key) = parse_query(query);
+    let failure = {
+        let mut state = state.lock().unwrap();
+        let request =
+            Request { service: service.to_owned(), names: names.clone(), api_key };
+        state.requests.push(request);
+        state.failures.pop_front()
+    };
+
+    if let Some(status) = failure {
+        return error(status, failure_message(status));
+    }
+    if names.is_empty() {
+        return error(422, "Missing 'name' parameter");
+    }
+    if names.iter().any(|name| name == INVALID_NAME) {
+        return error(422, "Invalid 'name' parameter");
+    }
+    if names.iter().any(|name| name == SLOW_NAME) {
+        thread::sleep(SLOW_DELAY);
+    }
+    let country = query.split('&').find_map(|param| param.strip_prefix("country_id="));
+    let state = state.lock().unwrap();
+    let results = names.iter().filter_map(|name| {
+        let mut result = state.fixtures.get(service, name)?;
+        if let Some(country) = country.filter(|_| service == "genderize") {
+            result["country_id"] = json!(decode(country));
+        }
+        Some(result)
+    });
+    (200, Value::Array(results.collect()))
+}
+
+/// Internal helper function: the names, and any API key, from a query string
+/// like "name[]=Anna&name[]=Peter&apikey=xyz".
+fn parse_query(query: &str) -> (Vec<String>, Option<String>) {
+    let mut names = Vec::new();
+    let mut api_key = None;
+    for param in query.split('&').filter(|param| !param.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        let (key, value) = (decode(key), decode(value));
+        match key.as_str() {
+            "name" | "name[]" => names.push(value),
+            "apikey" => api_key = Some(value),
+            _ => {}
+        }
+    }
+    (names, api_key)
+}
+
+/// Internal helper function: decode one query string component.
+fn decode(component: &str) -> String {
+    let component = component.replace('+', " ");
+    urlencoding::decode(&component)
+        .map_or(component.clone(), |decoded| decoded.into_owned())
+}
+
+/// Internal helper function: roughly what the API says for each error status.
+fn failure_message(status: u16) -> &'static str {
+    match status {
+        401 => "Invalid API key",
+        402 => "Subscription is not active",
+        422 => "Invalid 'name' parameter",
+        429 => "Request limit reached",
+        _ => "Internal server error",
+    }
+}
+
+/// Internal helper function: an error response, in the API's format.
+fn error(status: u16, message: &str) -> (u16, Value) {
+    (status, json!({ "error": message }))
+}
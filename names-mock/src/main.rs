@@ -0,0 +1,39 @@
+//! Run the mock API server on its own, eg to point the app or names-cli at
+#![warn(rust_2018_idioms, missing_docs, unused)]
+
+use std::path::PathBuf;
+use std::thread;
+
+use clap::Parser;
+use names_mock::{BoxError, Fixtures, MockApi};
+
+/// Stand-in for the genderize.io, nationalize.io and agify.io APIs, answering
+/// from fixture files.
+///
+/// Point the app at it via the proxy URL in its settings, or names-cli via
+/// --api-url.
+#[derive(Parser, Debug)]
+#[command(name = "names-mock", version)]
+struct Args {
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8081")]
+    listen: String,
+
+    /// Directory of {genderize,nationalize,agify}.json fixtures (default: the
+    /// bundled ones)
+    #[arg(long)]
+    fixtures: Option<PathBuf>,
+}
+
+fn main() -> Result<(), BoxError> {
+    let args = Args::parse();
+    let fixtures = match &args.fixtures {
+        Some(dir) => Fixtures::load(dir)?,
+        None => Fixtures::bundled(),
+    };
+    let mock = MockApi::serve(&args.listen, fixtures)?;
+    eprintln!("Mock API listening on {}", mock.url());
+    loop {
+        thread::park();
+    }
+}
@@ -0,0 +1,168 @@
+//! The names-core API client against the mock API: results, and each of the
+//! errors it has to make sense of.
+
+use futures::executor::block_on;
+use names_core::api::{
+    get_age, get_countries, get_countries_detail, get_gender_detail, get_genders,
+    get_raw, ApiConfig, ApiError, Gender, AGE_DOMAIN,
+};
+use names_core::native::UreqClient;
+use names_core::Name;
+use names_mock::{MockApi, INVALID_NAME, SLOW_NAME};
+
+/// Internal helper function: config for going via the mock.
+fn config(mock: &MockApi) -> ApiConfig {
+    ApiConfig { base_url: Some(mock.url().to_owned()), ..ApiConfig::default() }
+}
+
+/// Internal helper function: a list of names.
+fn names(names: &[&str]) -> Vec<Name> {
+    names.iter().map(|&name| Name::from_ref(name)).collect()
+}
+
+#[test]
+fn genders() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let results = block_on(get_genders(
+        &client,
+        &config(&mock),
+        &names(&["Anna", "Peter", "Xyzzy"]),
+    ))
+    .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results["Anna"].gender, Some(Gender::Female));
+    assert_eq!(results["Peter"].gender, Some(Gender::Male));
+    assert_eq!(results["Xyzzy"].gender, None);
+    assert_eq!(mock.requests()[0].service, "genderize");
+}
+
+#[test]
+fn countries() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let results =
+        block_on(get_countries(&client, &config(&mock), &names(&["Jean"]))).unwrap();
+    assert!(!results["Jean"].is_empty());
+    assert_eq!(mock.requests()[0].service, "nationalize");
+}
+
+#[test]
+fn single_name_details() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let config = config(&mock);
+    let andrea = Name::from_static("Andrea");
+
+    let gender = block_on(get_gender_detail(&client, &config, &andrea, None)).unwrap();
+    assert_eq!(gender.result.gender, Some(Gender::Female));
+    assert!(gender.json.contains("\"count\":337373"), "{}", gender.json);
+    let localised =
+        block_on(get_gender_detail(&client, &config, &andrea, Some("IT"))).unwrap();
+    assert!(localised.json.contains("\"country_id\":\"IT\""), "{}", localised.json);
+
+    let countries = block_on(get_countries_detail(&client, &config, &andrea)).unwrap();
+    assert!(!countries.result.is_empty());
+    let age = block_on(get_age(&client, &config, &andrea)).unwrap();
+    assert_eq!(age.result.age, Some(44));
+
+    let services: Vec<_> = mock.requests().into_iter().map(|r| r.service).collect();
+    assert_eq!(services, ["genderize", "genderize", "nationalize", "agify"]);
+}
+
+#[test]
+fn raw_results() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let config = config(&mock);
+    let raw = block_on(get_raw(
+        &client,
+        &config,
+        AGE_DOMAIN,
+        &names(&["Andrea", "Peter"]),
+        None,
+    ))
+    .unwrap();
+    assert_eq!(raw.len(), 2);
+    assert!(raw[0].contains("\"name\":\"Andrea\""), "{}", raw[0]);
+    assert!(raw[1].contains("\"name\":\"Peter\""), "{}", raw[1]);
+    let localised =
+        block_on(get_raw(&client, &config, "genderize", &names(&["Jean"]), Some("FR")))
+            .unwrap();
+    assert!(localised[0].contains("\"country_id\":\"FR\""), "{}", localised[0]);
+}
+
+#[test]
+fn no_result() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let unheardof = Name::from_static("Unheardof");
+    let result = block_on(get_age(&client, &config(&mock), &unheardof));
+    assert!(matches!(result, Err(ApiError::BadResponse(_))));
+}
+
+#[test]
+fn partial_batch() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let results =
+        block_on(get_genders(&client, &config(&mock), &names(&["Anna", "Unheardof"])))
+            .unwrap();
+    assert!(results.contains_key("Anna"));
+    assert!(!results.contains_key("Unheardof"));
+}
+
+#[test]
+fn limit_exceeded() {
+    let mock = MockApi::start();
+    mock.fail_next(429, 1);
+    let client = UreqClient::new(5);
+    let result = block_on(get_genders(&client, &config(&mock), &names(&["Anna"])));
+    assert!(matches!(result, Err(ApiError::LimitExceeded)));
+}
+
+#[test]
+fn server_error() {
+    let mock = MockApi::start();
+    mock.fail_next(503, 1);
+    let client = UreqClient::new(5);
+    let result = block_on(get_genders(&client, &config(&mock), &names(&["Anna"])));
+    assert!(matches!(result, Err(ApiError::ServerError(503, _))));
+}
+
+#[test]
+fn invalid_api_key() {
+    let mock = MockApi::start();
+    mock.fail_next(401, 1);
+    let client = UreqClient::new(5);
+    let result = block_on(get_genders(&client, &config(&mock), &names(&["Anna"])));
+    assert!(
+        matches!(result, Err(ApiError::InvalidApiKey(message)) if message == "Invalid API key")
+    );
+}
+
+#[test]
+fn invalid_name() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let result =
+        block_on(get_genders(&client, &config(&mock), &names(&["Anna", INVALID_NAME])));
+    assert!(matches!(result, Err(ApiError::InvalidName(_))));
+}
+
+#[test]
+fn timeout() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(1);
+    let result = block_on(get_genders(&client, &config(&mock), &names(&[SLOW_NAME])));
+    assert_eq!(result, Err(ApiError::Timeout));
+}
+
+#[test]
+fn api_key_passed_on() {
+    let mock = MockApi::start();
+    let client = UreqClient::new(5);
+    let config = ApiConfig { api_key: Some("secret".to_owned()), ..config(&mock) };
+    block_on(get_genders(&client, &config, &names(&["Anna"]))).unwrap();
+    assert_eq!(mock.requests()[0].api_key.as_deref(), Some("secret"));
+}
@@ -0,0 +1,136 @@
+//! The names-core request queue, driven against the mock API: batching,
+//! backing off, isolating invalid names, and lookups for single names. Backoffs
+//! are ended straight away, rather than waited out, so this all runs quickly.
+
+use futures::executor::block_on;
+use names_core::api::{get_age, get_genders, ApiConfig, Service};
+use names_core::native::UreqClient;
+use names_core::queue::{Outcome, Queue, API_CHUNKS};
+use names_core::Name;
+use names_mock::{MockApi, INVALID_NAME};
+
+/// Internal helper function: run everything queued for genders through the
+/// mock, returning every outcome other than [Outcome::Done], and the number of
+/// results.
+fn run(queue: &mut Queue<()>, mock: &MockApi) -> (Vec<Outcome>, usize) {
+    let client = UreqClient::new(5);
+    let config =
+        ApiConfig { base_url: Some(mock.url().to_owned()), ..ApiConfig::default() };
+    let (mut outcomes, mut results) = (Vec::new(), 0);
+    let mut now = 0.0;
+    while let Some(batch) = queue.start_batch(Service::Gender, now, || ()) {
+        now += 100.0;
+        let result = block_on(get_genders(&client, &config, &batch.names));
+        let error = result.as_ref().err();
+        match queue.finished(Service::Gender, batch, error, now, true) {
+            Outcome::Done => results += result.unwrap().len(),
+            Outcome::BackOff(delay) => {
+                outcomes.push(Outcome::BackOff(delay));
+                queue.end_backoff(Service::Gender);
+            }
+            outcome => outcomes.push(outcome),
+        }
+    }
+    (outcomes, results)
+}
+
+/// Internal helper function: a queue of gender lookups.
+fn queue(names: impl IntoIterator<Item = Name>) -> Queue<()> {
+    let mut queue = Queue::default();
+    for name in names {
+        queue.enqueue(Service::Gender, name);
+    }
+    queue
+}
+
+/// Internal helper function: lots of different names.
+fn many(count: usize) -> impl Iterator<Item = Name> {
+    (0..count).map(|i| format!("Name{i}").into())
+}
+
+#[test]
+fn batches() {
+    let mock = MockApi::start();
+    let mut queue = queue(many(25));
+    let (outcomes, _) = run(&mut queue, &mock);
+    assert!(outcomes.is_empty());
+    let sizes: Vec<_> = mock.requests().iter().map(|r| r.names.len()).collect();
+    assert_eq!(sizes, [API_CHUNKS, API_CHUNKS, 5]);
+}
+
+#[test]
+fn backs_off_then_recovers() {
+    let mock = MockApi::start();
+    mock.fail_next(429, 1);
+    let mut queue = queue(["Anna".into(), "Peter".into()]);
+    let (outcomes, results) = run(&mut queue, &mock);
+    assert_eq!(outcomes, [Outcome::BackOff(2000)]);
+    assert_eq!(results, 2);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn gives_up_backing_off() {
+    let mock = MockApi::start();
+    mock.fail_next(429, 100);
+    let mut queue = queue(many(15));
+    let (outcomes, results) = run(&mut queue, &mock);
+    assert_eq!(results, 0);
+    match outcomes.last() {
+        Some(Outcome::Failed(names)) => assert_eq!(names.len(), 15),
+        other => panic!("expected everything to fail, not {other:?}"),
+    }
+    assert!(outcomes[..outcomes.len() - 1]
+        .iter()
+        .all(|outcome| matches!(outcome, Outcome::BackOff(_))));
+}
+
+#[test]
+fn isolates_invalid_name() {
+    let mock = MockApi::start();
+    let names = ["Anna", "Peter", INVALID_NAME, "Andrea", "Jean"];
+    let mut queue = queue(names.iter().map(|&name| name.into()));
+    let (outcomes, results) = run(&mut queue, &mock);
+    let failed: Vec<_> = outcomes
+        .into_iter()
+        .filter_map(|outcome| match outcome {
+            Outcome::Failed(names) => Some(names),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(failed, [vec![Name::from(INVALID_NAME)]]);
+    assert_eq!(results, 4);
+}
+
+#[test]
+fn single_name_lookups() {
+    let mock = MockApi::start();
+    mock.fail_next(429, 1);
+    let client = UreqClient::new(5);
+    let config =
+        ApiConfig { base_url: Some(mock.url().to_owned()), ..ApiConfig::default() };
+    let mut queue: Queue<(), Name> = Queue::default();
+    queue.set_paused(true);
+    for name in ["Andrea", "Peter"] {
+        queue.enqueue_detail(name.into());
+    }
+    assert!(queue.start_detail(0.0, || ()).is_none());
+    queue.set_paused(false);
+
+    let mut outcomes = Vec::new();
+    while let Some(batch) = queue.start_detail(0.0, || ()) {
+        assert_eq!(batch.names.len(), 1);
+        let result = block_on(get_age(&client, &config, &batch.names[0]));
+        match queue.finished_detail(batch, result.as_ref().err(), 100.0, true) {
+            Outcome::BackOff(delay) => {
+                // Nothing more goes out until the backoff is over.
+                assert!(queue.start_detail(0.0, || ()).is_none());
+                outcomes.push(Outcome::BackOff(delay));
+                queue.end_detail_backoff();
+            }
+            outcome => outcomes.push(outcome),
+        }
+    }
+    assert_eq!(outcomes, [Outcome::BackOff(2000), Outcome::Done, Outcome::Done]);
+    assert_eq!(mock.requests().len(), 3);
+}
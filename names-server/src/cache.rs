@@ -0,0 +1,88 @@
+//! SQLite cache of raw API results, one row per name per endpoint: the service
+//! (eg "genderize"), plus any country the results are localised to (eg
+//! "genderize:FR").
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use names_core::Name;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Cached API results, shared between the worker threads
+pub struct Cache {
+    conn: Mutex<Connection>,
+    /// Number of seconds after which a result is fetched afresh, if any
+    max_age: Option<i64>,
+}
+
+impl Cache {
+    /// Open (or create) the cache database.
+    pub fn open(path: &Path, max_age: Option<i64>) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("opening {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS results (
+                service TEXT NOT NULL,
+                name TEXT NOT NULL,
+                json TEXT NOT NULL,
+                fetched INTEGER NOT NULL,
+                PRIMARY KEY (service, name)
+            )",
+        )
+        .context("creating cache table")?;
+        Ok(Cache { conn: Mutex::new(conn), max_age })
+    }
+
+    /// The cached raw JSON result for each of the given names we have.
+    pub fn get(
+        &self,
+        endpoint: &str,
+        names: &[Name],
+    ) -> anyhow::Result<HashMap<Name, String>> {
+        let oldest = match self.max_age {
+            Some(max_age) => now() - max_age,
+            None => 0,
+        };
+        let conn = self.conn.lock().unwrap();
+        let mut query = conn.prepare_cached(
+            "SELECT json FROM results WHERE service = ?1 AND name = ?2 AND fetched >= ?3",
+        )?;
+        let mut found = HashMap::new();
+        for name in names {
+            let json = query
+                .query_row(params![endpoint, name.as_str(), oldest], |row| {
+                    row.get::<_, String>(0)
+                })
+                .optional()?;
+            if let Some(json) = json {
+                found.insert(name.clone(), json);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Store freshly fetched raw JSON results.
+    pub fn put(&self, endpoint: &str, results: &[(Name, String)]) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT OR REPLACE INTO results (service, name, json, fetched)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (name, json) in results {
+                insert.execute(params![endpoint, name.as_str(), json, now()])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Current time, in seconds since the epoch
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs() as i64)
+}
@@ -0,0 +1,38 @@
+//! Serve the built frontend (the output of `trunk build`).
+
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+/// File to serve for any path that isn't a file, so the app can handle its own
+/// routes
+const INDEX: &str = "index.html";
+
+/// The file to serve for a URL path within the frontend directory, if any.
+/// Anything trying to escape the directory gets nothing.
+pub fn find(dir: &Path, url_path: &str) -> Option<(File, &'static str)> {
+    let decoded = urlencoding::decode(url_path).ok()?;
+    let relative = PathBuf::from(decoded.trim_start_matches('/'));
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let path = dir.join(relative);
+    let path = if path.is_file() { path } else { dir.join(INDEX) };
+    let file = File::open(&path).ok()?;
+    Some((file, content_type(&path)))
+}
+
+/// Internal helper function: the MIME type for a file. (Serving `.wasm` files
+/// as `application/wasm` matters, since browsers only stream-compile those.)
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "application/javascript",
+        Some("wasm") => "application/wasm",
+        Some("css") => "text/css",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
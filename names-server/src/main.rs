@@ -0,0 +1,141 @@
+//! Self-hostable server for the names app: serves the built frontend, and
+//! proxies its genderize.io, nationalize.io and agify.io requests, caching the
+//! results, so a whole team shares one cache (and optionally one API key).
+#![warn(rust_2018_idioms, missing_docs, unused)]
+
+mod cache;
+mod files;
+mod proxy;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::anyhow;
+use clap::Parser;
+use names_core::api::{ApiConfig, Service, AGE_DOMAIN};
+use names_core::native::UreqClient;
+use tiny_http::{Header, Method, Request, Response, ResponseBox, Server};
+
+use crate::cache::Cache;
+
+/// Serve the names app, proxying and caching its API requests.
+///
+/// Point the app at this server by building it with NAMES_API_BASE_URL=/ (or
+/// setting the proxy URL in the app's settings).
+#[derive(Parser, Debug)]
+#[command(name = "names-server", version)]
+struct Args {
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// Directory containing the built frontend (the output of `trunk build`)
+    #[arg(long, default_value = "dist")]
+    dist: PathBuf,
+
+    /// SQLite cache database
+    #[arg(long, default_value = "names-cache.sqlite")]
+    db: PathBuf,
+
+    /// Number of days after which cached results are fetched afresh (default:
+    /// never)
+    #[arg(long)]
+    max_age_days: Option<u32>,
+
+    /// API key, for a paid plan with a higher quota. This stays on the server:
+    /// without one, any key the app sends is passed on instead.
+    #[arg(long, env = "NAMES_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Base URL to send API requests to, instead of the API services
+    /// themselves (eg a names-mock server, for testing)
+    #[arg(long)]
+    upstream: Option<String>,
+
+    /// Number of seconds to wait for each API request
+    #[arg(long, default_value_t = 30)]
+    timeout: u32,
+
+    /// Number of worker threads
+    #[arg(long, default_value_t = 4)]
+    threads: usize,
+}
+
+/// Everything the worker threads share
+struct State {
+    cache: Cache,
+    client: UreqClient,
+    /// Our own API key, if any, and where to send API requests
+    config: ApiConfig,
+    dist: PathBuf,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let max_age = args.max_age_days.map(|days| i64::from(days) * 24 * 60 * 60);
+    let state = Arc::new(State {
+        cache: Cache::open(&args.db, max_age)?,
+        client: UreqClient::new(args.timeout),
+        config: ApiConfig {
+            api_key: args.api_key.filter(|key| !key.trim().is_empty()),
+            base_url: args.upstream,
+        },
+        dist: args.dist,
+    });
+    let server = Arc::new(Server::http(&args.listen).map_err(|err| anyhow!(err))?);
+    eprintln!("Listening on http://{}", args.listen);
+
+    let workers: Vec<_> = (0..args.threads.max(1))
+        .map(|_| {
+            let (server, state) = (server.clone(), state.clone());
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    respond(&state, request);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        _ = worker.join();
+    }
+    Ok(())
+}
+
+/// Handle one request: an API call if it's for one of the API services, and
+/// otherwise part of the frontend.
+fn respond(state: &State, request: Request) {
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let response = match (request.method(), path.trim_end_matches('/')) {
+        (Method::Get, "/genderize") => api(state, Service::Gender.domain(), query),
+        (Method::Get, "/nationalize") => api(state, Service::Country.domain(), query),
+        (Method::Get, "/agify") => api(state, AGE_DOMAIN, query),
+        (Method::Get | Method::Head, _) => match files::find(&state.dist, path) {
+            Some((file, content_type)) => Response::from_file(file)
+                .with_header(header("Content-Type", content_type))
+                .boxed(),
+            None => Response::from_string("Not found").with_status_code(404).boxed(),
+        },
+        _ => Response::from_string("Method not allowed").with_status_code(405).boxed(),
+    };
+    if let Err(err) = request.respond(response) {
+        eprintln!("Error responding to {path}: {err}");
+    }
+}
+
+/// Internal helper function: answer an API call. These may come from an app
+/// hosted elsewhere, so are allowed cross-origin.
+fn api(state: &State, domain: &str, query: &str) -> ResponseBox {
+    let reply = proxy::handle(domain, query, &state.cache, &state.client, &state.config);
+    Response::from_string(reply.json)
+        .with_status_code(reply.status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .boxed()
+}
+
+/// Internal helper function: make a response header.
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+}
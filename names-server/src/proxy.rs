@@ -0,0 +1,263 @@
+//! Proxy requests through to the API services, answering what we can from the
+//! [Cache].
+
+use std::collections::HashSet;
+
+use futures::executor::block_on;
+use names_core::api::{get_raw, ApiConfig, ApiError, RawError};
+use names_core::native::UreqClient;
+use names_core::queue::API_CHUNKS;
+use names_core::Name;
+
+use crate::cache::Cache;
+
+/// A JSON response, and its HTTP status
+pub struct Reply {
+    pub status: u16,
+    pub json: String,
+}
+
+/// The parts of a request's query string we pass on
+struct Query {
+    names: Vec<Name>,
+    api_key: Option<String>,
+    /// ISO 3166 alpha-2 code of the country to localise the results to, if any
+    country: Option<String>,
+}
+
+/// Answer a request for the API with the given domain name stem (eg
+/// "genderize"), given the URL query string, in the same format as the API
+/// itself (including errors). Requests go where `config` says, with its API
+/// key if it has one.
+pub fn handle(
+    domain: &str,
+    query: &str,
+    cache: &Cache,
+    client: &UreqClient,
+    config: &ApiConfig,
+) -> Reply {
+    let Query { names, api_key: client_key, country } = parse_query(query);
+    if names.is_empty() {
+        return error(422, "Missing 'name' parameter");
+    }
+    if names.len() > API_CHUNKS {
+        return error(422, &format!("At most {API_CHUNKS} names per request"));
+    }
+
+    // Our own API key, if we have one, or else the client's
+    let config =
+        ApiConfig { api_key: config.api_key.clone().or(client_key), ..config.clone() };
+    match lookup(domain, country.as_deref(), &names, cache, client, &config) {
+        Ok(results) => Reply { status: 200, json: format!("[{}]", results.join(",")) },
+        Err(Failure::Api(err)) => error(status(&err), &message(&err)),
+        Err(Failure::Cache(err)) => {
+            eprintln!("Cache error: {err:#}");
+            error(500, "Cache error")
+        }
+    }
+}
+
+/// Why a request couldn't be answered
+enum Failure {
+    Api(ApiError),
+    Cache(anyhow::Error),
+}
+
+/// Internal helper function: the raw JSON result for each name, in order,
+/// fetching any we don't already have. Results localised to a country are
+/// cached separately from the rest, eg under "genderize:FR".
+fn lookup(
+    domain: &str,
+    country: Option<&str>,
+    names: &[Name],
+    cache: &Cache,
+    client: &UreqClient,
+    config: &ApiConfig,
+) -> Result<Vec<String>, Failure> {
+    let endpoint = match country {
+        Some(country) => format!("{domain}:{}", country.to_ascii_uppercase()),
+        None => domain.to_owned(),
+    };
+    let mut results = cache.get(&endpoint, names).map_err(Failure::Cache)?;
+    let mut seen = HashSet::new();
+    let missing: Vec<Name> = names
+        .iter()
+        .filter(|name| !results.contains_key(*name) && seen.insert(*name))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        let raw = block_on(get_raw(client, config, domain, &missing, country))
+            .map_err(Failure::Api)?;
+        let fetched: Vec<(Name, String)> = missing.into_iter().zip(raw).collect();
+        cache.put(&endpoint, &fetched).map_err(Failure::Cache)?;
+        results.extend(fetched);
+    }
+    Ok(names.iter().filter_map(|name| results.get(name).cloned()).collect())
+}
+
+/// Internal helper function: the names, and any API key and country, from a
+/// query string like the API's own, eg "name[]=Anna&name[]=Bob&apikey=xyz".
+fn parse_query(query: &str) -> Query {
+    let mut names = Vec::new();
+    let mut api_key = None;
+    let mut country = None;
+    for param in query.split('&').filter(|param| !param.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        let (key, value) = (decode(key), decode(value));
+        match key.as_str() {
+            "name" | "name[]" if !value.is_empty() => {
+                names.push(Name::from_string(value))
+            }
+            "apikey" if !value.is_empty() => api_key = Some(value),
+            "country_id" if !value.is_empty() => country = Some(value),
+            _ => {}
+        }
+    }
+    Query { names, api_key, country }
+}
+
+/// Internal helper function: decode one query string component.
+fn decode(component: &str) -> String {
+    let component = component.replace('+', " ");
+    urlencoding::decode(&component)
+        .map_or(component.clone(), |decoded| decoded.into_owned())
+}
+
+/// HTTP status to pass on an API error to the client with, so it is
+/// interpreted the same way as if it came from the API directly.
+fn status(err: &ApiError) -> u16 {
+    match err {
+        ApiError::InvalidApiKey(_) => 401,
+        ApiError::SubscriptionInactive(_) => 402,
+        ApiError::InvalidName(_) => 422,
+        ApiError::LimitExceeded => 429,
+        ApiError::ServerError(status, _) => *status,
+        ApiError::Timeout => 504,
+        ApiError::Http(_) | ApiError::BadResponse(_) => 502,
+    }
+}
+
+/// The message to pass on an API error to the client with, which is the
+/// original one from the API, where there was one.
+fn message(err: &ApiError) -> String {
+    match err {
+        ApiError::InvalidApiKey(message)
+        | ApiError::SubscriptionInactive(message)
+        | ApiError::InvalidName(message)
+        | ApiError::ServerError(_, message) => message.clone(),
+        _ => err.to_string(),
+    }
+}
+
+/// Internal helper function: an error reply, in the API's own format.
+fn error(status: u16, message: &str) -> Reply {
+    let json = serde_json::to_string(&RawError { error: message.to_owned() })
+        .unwrap_or_default();
+    Reply { status, json }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use names_mock::{MockApi, SLOW_NAME};
+
+    use super::*;
+
+    /// Internal helper function: an empty cache.
+    fn cache() -> Cache { Cache::open(Path::new(":memory:"), None).unwrap() }
+
+    /// Internal helper function: config for going via the mock.
+    fn config(mock: &MockApi) -> ApiConfig {
+        ApiConfig { base_url: Some(mock.url().to_owned()), ..ApiConfig::default() }
+    }
+
+    #[test]
+    fn query() {
+        let query =
+            parse_query("name[]=Anna&name=Zo%C3%AB&name[]=&apikey=xyz&country_id=fr");
+        assert_eq!(query.names, [Name::from_ref("Anna"), Name::from_ref("Zoë")]);
+        assert_eq!(query.api_key.as_deref(), Some("xyz"));
+        assert_eq!(query.country.as_deref(), Some("fr"));
+
+        let query = parse_query("name[]=Mary+Ann&apikey=&country_id=&other=1");
+        assert_eq!(query.names, [Name::from_ref("Mary Ann")]);
+        assert_eq!(query.api_key, None);
+        assert_eq!(query.country, None);
+    }
+
+    #[test]
+    fn cached_per_endpoint() {
+        let mock = MockApi::start();
+        let (cache, client, config) = (cache(), UreqClient::new(5), config(&mock));
+        let get =
+            |domain: &str, query: &str| handle(domain, query, &cache, &client, &config);
+        for (domain, query) in [
+            ("genderize", "name[]=Anna"),
+            ("genderize", "name[]=Anna&name[]=Peter"),
+            ("genderize", "name[]=Anna&country_id=FR"),
+            ("genderize", "name[]=Anna&country_id=fr"),
+            ("nationalize", "name[]=Anna"),
+            ("genderize", "name[]=Peter&name[]=Anna"),
+        ] {
+            assert_eq!(get(domain, query).status, 200, "{domain}?{query}");
+        }
+
+        // Only names not already cached for that endpoint are fetched.
+        let requests: Vec<_> = mock
+            .requests()
+            .into_iter()
+            .map(|request| (request.service, request.names.join(",")))
+            .collect();
+        let expected = [
+            ("genderize", "Anna"),
+            ("genderize", "Peter"),
+            ("genderize", "Anna"),
+            ("nationalize", "Anna"),
+        ];
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(service, names)| (service.to_owned(), names.to_owned()))
+            .collect();
+        assert_eq!(requests, expected);
+
+        // Results come back in the order asked for, whether cached or not.
+        let reply = get("genderize", "name[]=Peter&name[]=Anna");
+        let results: Vec<serde_json::Value> = serde_json::from_str(&reply.json).unwrap();
+        let names: Vec<_> = results.iter().map(|result| &result["name"]).collect();
+        assert_eq!(names, ["Peter", "Anna"]);
+    }
+
+    #[test]
+    fn statuses() {
+        let mock = MockApi::start();
+        let (client, config) = (UreqClient::new(1), config(&mock));
+        for status in [401, 402, 422, 429, 503] {
+            mock.fail_next(status, 1);
+            let reply = handle("genderize", "name[]=Anna", &cache(), &client, &config);
+            assert_eq!(reply.status, status);
+            assert!(reply.json.contains("\"error\""), "{}", reply.json);
+        }
+        let query = format!("name[]={SLOW_NAME}");
+        assert_eq!(handle("genderize", &query, &cache(), &client, &config).status, 504);
+
+        // Nothing listening
+        let unreachable = ApiConfig {
+            base_url: Some("http://127.0.0.1:1".to_owned()),
+            ..config.clone()
+        };
+        assert_eq!(
+            handle("genderize", "name[]=Anna", &cache(), &client, &unreachable).status,
+            502
+        );
+
+        // Requests the API would reject anyway don't get as far as it.
+        assert_eq!(
+            handle("genderize", "apikey=xyz", &cache(), &client, &config).status,
+            422
+        );
+        let many = ["name[]=Anna"; API_CHUNKS + 1].join("&");
+        assert_eq!(handle("genderize", &many, &cache(), &client, &config).status, 422);
+        assert_eq!(mock.requests().len(), 6);
+    }
+}
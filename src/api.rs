@@ -1,148 +1,99 @@
-//! Wrap the API calls to get gender/country info
+//! Browser bindings for the API calls in [names_core::api]: requests go via
+//! `fetch`, and the API key lives in local storage.
 
-use reqwasm::http::{Request, Response};
-use serde::Deserialize;
+use futures::future::{select, Either, LocalBoxFuture};
+use gloo::storage::{LocalStorage, Storage};
+pub use names_core::api::*;
+use reqwasm::http::Request;
+use web_sys::AbortSignal;
 
 use crate::prelude::*;
 
-/// Errors from an API request.
-#[derive(thiserror::Error, Debug)]
-pub enum ApiError {
-    #[error("API request failed: {0}")]
-    Reqwasm(#[from] reqwasm::Error),
-    #[error("Daily API limit exceeded - try again tomorrow")]
-    LimitExceeded,
-    #[error("Server returned error code {0} ({1})")]
-    ServerError(u16, String),
-}
+/// Local storage key for the user's API key
+const API_KEY_STORAGE_KEY: &str = "api_key";
 
-/// Generic type of an API result: either an [ApiError], or a mapping from first
-/// name to a result type.
-pub type ApiResult<T> = Result<HashMap<Name, T>, ApiError>;
+/// The user's API key, if they have one (for a paid plan with a higher quota).
+pub fn api_key() -> Option<String> { stored(API_KEY_STORAGE_KEY) }
 
-/// Fire off a bulk gender request.
-pub async fn get_genders(names: &[Name]) -> ApiResult<GenderResult> {
-    Ok(fetch("genderize", names)
-        .await?
-        .json::<RawGenderResults>()
-        .await?
-        .0
-        .into_iter()
-        .map(|r| {
-            (
-                r.name,
-                GenderResult {
-                    gender: r.gender,
-                    probability: r.probability,
-                    count: r.count,
-                },
-            )
-        })
-        .collect())
+/// Save the user's API key, or forget it if empty.
+pub fn set_api_key(key: &str) {
+    let key = key.trim();
+    if key.is_empty() {
+        LocalStorage::delete(API_KEY_STORAGE_KEY);
+    } else if let Err(err) = LocalStorage::set(API_KEY_STORAGE_KEY, key) {
+        tracing::error!("Unable to save API key: {:?}", err);
+    }
 }
 
-/// Fire off a bulk country request.
-pub async fn get_countries(names: &[Name]) -> ApiResult<Vec<CountryResult>> {
-    Ok(fetch("nationalize", names)
-        .await?
-        .json::<RawCountryResults>()
-        .await?
-        .0
-        .into_iter()
-        .map(|r| (r.name, r.country))
-        .collect())
+/// Local storage key for the base URL of any caching proxy
+const API_BASE_URL_STORAGE_KEY: &str = "api_base_url";
+
+/// Base URL of any caching proxy (names-server) to go via, rather than the APIs
+/// themselves. The user's own setting wins, but a self-hosted build can set a
+/// default with the `NAMES_API_BASE_URL` environment variable (eg "/" to use
+/// the server the app itself is served from).
+pub fn api_base_url() -> Option<String> {
+    stored(API_BASE_URL_STORAGE_KEY)
+        .or_else(|| option_env!("NAMES_API_BASE_URL").map(str::to_owned))
+        .filter(|url: &String| !url.is_empty())
 }
 
-/// Internal helper function: create an HTTP request, fire it off, and deal with
-/// the most common error cases.
-async fn fetch(domain: &str, names: &[Name]) -> Result<Response, ApiError> {
-    let url = format!("https://api.{}.io/{}", domain, fmt_params(names));
-    let response = Request::get(&url).send().await?;
-
-    // A successful reponse does not mean an HTTP 200, so turn an unhelpful
-    // server response into an error if appropriate, taking extra care for the
-    // most likely error case the user might need help interpreting.
-    let status = response.status();
-    if status == 429 {
-        Err(ApiError::LimitExceeded)
-    } else if status != 200 {
-        Err(ApiError::ServerError(status, response.status_text()))
-    } else {
-        Ok(response)
+/// Save the base URL of a caching proxy, or forget it if empty.
+pub fn set_api_base_url(url: &str) {
+    let url = url.trim();
+    if url.is_empty() {
+        LocalStorage::delete(API_BASE_URL_STORAGE_KEY);
+    } else if let Err(err) = LocalStorage::set(API_BASE_URL_STORAGE_KEY, url) {
+        tracing::error!("Unable to save API base URL: {:?}", err);
     }
 }
 
-/// Internal helper function: format the parameters. Do it manually, rather than
-/// a crate, since the keys are unencoded, but the values are, and the popular
-/// crates don't have a way of doing that which is simpler than just spelling it
-/// our ourselves here.
-fn fmt_params(names: &[Name]) -> String {
-    let mut params = String::default();
-    let mut first_param = true;
-    for name in names {
-        let sep = if first_param {
-            first_param = false;
-            '?'
-        } else {
-            '&'
-        };
-        params.push_str(&format!("{}name[]={}", sep, urlencoding::encode(name)));
-    }
-    params
+/// Internal helper function: a setting from local storage, if any. There's no
+/// local storage without a browser (eg under `cargo test`).
+fn stored(key: &str) -> Option<String> {
+    window()?;
+    LocalStorage::get(key).ok()
 }
 
-//////////////////////////////////////////////////////////////////////////////
-
-/// Gender result for one [Name]
-#[derive(Debug, Clone)]
-pub struct GenderResult {
-    pub gender: Option<Gender>,
-    pub probability: f32,
-    pub count: u32,
+/// How to reach the APIs from the browser, given the current settings.
+pub fn api_config() -> ApiConfig {
+    ApiConfig { api_key: api_key(), base_url: api_base_url() }
 }
 
-/// Gender
-#[derive(Deserialize, Debug, Clone, Copy)]
-#[serde(rename_all = "lowercase")]
-pub enum Gender {
-    Female,
-    Male,
+/// [HttpClient] using the browser's `fetch`, optionally abortable, and giving
+/// up after a timeout (in milliseconds).
+pub struct WebClient<'a> {
+    pub signal: Option<&'a AbortSignal>,
+    pub timeout: u32,
 }
 
-//////////////////////////////////////////////////////////////////////////////
-
-/// Single country result for one [Name]
-#[derive(Debug, Clone, Deserialize)]
-pub struct CountryResult {
-    #[serde(rename = "country_id")]
-    pub country: String,
-    pub probability: f32,
-}
-
-//////////////////////////////////////////////////////////////////////////////
-//
-// Internals (raw API representations)
-
-/// Direct representation of a gender API result set.
-#[derive(Deserialize, Debug)]
-pub struct RawGenderResults(pub Vec<RawGenderResult>);
-
-/// Direct representation of a single gender API result.
-#[derive(Deserialize, Debug)]
-pub struct RawGenderResult {
-    pub name: Name,
-    pub gender: Option<Gender>,
-    pub probability: f32,
-    pub count: u32,
+impl HttpClient for WebClient<'_> {
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, ApiError>> {
+        Box::pin(async move {
+            // The timeout covers reading the body too, since a slow server can
+            // just as well stall partway through that.
+            let fetch = Box::pin(async move {
+                let response = Request::get(url)
+                    .abort_signal(self.signal)
+                    .send()
+                    .await
+                    .map_err(http_error)?;
+                Ok(HttpResponse {
+                    status: response.status(),
+                    status_text: response.status_text(),
+                    body: response.text().await.map_err(http_error)?,
+                })
+            });
+            match select(fetch, TimeoutFuture::new(self.timeout)).await {
+                Either::Left((response, _)) => response,
+                Either::Right(_) => Err(ApiError::Timeout),
+            }
+        })
+    }
 }
 
-/// Direct representation of a country API result set.
-#[derive(Deserialize, Debug)]
-pub struct RawCountryResults(pub Vec<RawCountryResult>);
-
-/// Direct representation of a single country API result.
-#[derive(Deserialize, Debug)]
-pub struct RawCountryResult {
-    pub name: Name,
-    pub country: Vec<CountryResult>,
-}
+/// Internal helper function: wrap up a failed `fetch`.
+fn http_error(err: reqwasm::Error) -> ApiError { ApiError::Http(err.to_string()) }
@@ -0,0 +1,188 @@
+//! Groups of spelling variants among the names entered, with combined results
+//! for each group
+
+use std::rc::Rc;
+
+use names_core::cluster::{clusters, combine_countries, combine_genders, Edit};
+
+use super::table::RenderRemote;
+use crate::prelude::*;
+use crate::settings::LOCALE;
+
+/// The names and edits last grouped, and the resulting groups
+type Grouped = (Rc<Names>, Rc<Vec<Edit>>, Rc<Vec<Vec<Name>>>);
+
+// Component to display the groups of spelling variants (see
+// [names_core::cluster]) among the names entered, with each group's gender and
+// countries combined from the API results for its names. Only groups of more
+// than one name are shown. The user can split a name out of its group, or
+// merge in the group of any other name in the list.
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn Clusters(
+    cx: Scope<'_>,
+    names: UseState<Names>,
+    db: UseRef<Db>,
+    edits: UseState<Vec<Edit>>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+
+    // Only regroup when the names or edits change, rather than whenever more
+    // results come in.
+    let (current_names, current_edits) = (names.current(), edits.current());
+    let grouped = cx.use_hook(|_| Grouped::default());
+    if !Rc::ptr_eq(&grouped.0, &current_names) || !Rc::ptr_eq(&grouped.1, &current_edits)
+    {
+        let groups = clusters(&current_names, &current_edits);
+        *grouped = (current_names, current_edits, Rc::new(groups));
+    }
+    let groups: Vec<_> = grouped.2.iter().filter(|group| group.len() > 1).collect();
+    if groups.is_empty() {
+        return None;
+    }
+
+    let db = db.read();
+    let rows = groups.into_iter().map(|group| {
+        let (gender, country) = combined(&db, group);
+        let first = &group[0];
+        rsx! {
+            Cluster {
+                key: "{first}",
+                names: group.clone(),
+                gender: gender,
+                country: country,
+                all_names: names.clone(),
+                edits: edits.clone()
+            }
+        }
+    });
+    let heading = msgs.variants_heading;
+
+    cx.render(rsx! {
+        div {
+            class: "clusters",
+            h5 { "{heading}" }
+            table {
+                class: "table table-sm table-bordered",
+                rows
+            }
+        }
+    })
+}
+
+// One group of variants, as a table row
+#[inline_props]
+fn Cluster(
+    cx: Scope<'_>,
+    names: Vec<Name>,
+    gender: Remote<GenderResult>,
+    country: Remote<Vec<CountryResult>>,
+    all_names: UseState<Names>,
+    edits: UseState<Vec<Edit>>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+    let (split_out, merge_placeholder, merge_title) =
+        (msgs.split_out, msgs.merge_placeholder, msgs.merge_title);
+
+    let members = names.iter().map(|name| {
+        let split = Edit::Split(name.clone());
+        rsx! {
+            span {
+                key: "{name}",
+                class: "variant",
+                "{name}"
+                button {
+                    class: "close",
+                    title: "{split_out}",
+                    onclick: move |_| add_edit(edits, split.clone()),
+                    "×"
+                }
+            }
+        }
+    });
+    let gender = gender.render(msgs, |r| {
+        let (label, count) = (r.summarised(), r.count);
+        rsx! {
+            ConfidenceBar { probability: r.probability }
+            "{label} "
+            span { class: "count", "({count})" }
+        }
+    });
+    let country = country.render(msgs, |r| rsx! { Countries { countries: r.clone() } });
+
+    // Merge in the group of whichever name is entered, if it's in the list.
+    let onchange = move |ev: FormEvent| {
+        let other = ev.value.trim();
+        if let Some(other) = all_names.iter().find(|n| n.eq_ignore_ascii_case(other)) {
+            add_edit(edits, Edit::Merge(names[0].clone(), other.clone()));
+        }
+    };
+
+    cx.render(rsx! {
+        tr {
+            td { members }
+            td { gender }
+            td { country }
+            td {
+                input {
+                    class: "form-control form-control-sm merge",
+                    placeholder: "{merge_placeholder}",
+                    title: "{merge_title}",
+                    onchange: onchange
+                }
+            }
+        }
+    })
+}
+
+/// Internal helper function: the combined results for a group, as soon as any
+/// of its names have results, or otherwise whether they're still loading.
+fn combined(
+    db: &Db,
+    group: &[Name],
+) -> (Remote<GenderResult>, Remote<Vec<CountryResult>>) {
+    let results: Vec<&AllResults> =
+        group.iter().filter_map(|name| db.get(name)).collect();
+    let genders = results.iter().filter_map(|results| match &results.gender {
+        Remote::Success(gender) => Some(gender),
+        _ => None,
+    });
+    // Each name's countries are weighted by the number of people behind its
+    // gender result, since the country API doesn't say.
+    let countries = results.iter().filter_map(|results| match &results.country {
+        Remote::Success(countries) => {
+            let count = match &results.gender {
+                Remote::Success(gender) => gender.count,
+                _ => 0,
+            };
+            Some((countries.as_slice(), count))
+        }
+        _ => None,
+    });
+    // (Names still being typed may not have been queried yet.)
+    let loading = |loading: fn(&AllResults) -> bool| {
+        group.iter().any(|name| match db.get(name) {
+            Some(results) => loading(results),
+            None => true,
+        })
+    };
+    let gender = match combine_genders(genders) {
+        Some(gender) => Remote::Success(gender),
+        None if loading(|r| matches!(r.gender, Remote::Loading)) => Remote::Loading,
+        None => Remote::Error,
+    };
+    let countries: Vec<_> = countries.collect();
+    let country = match countries.is_empty() {
+        false => Remote::Success(combine_countries(countries)),
+        true if loading(|r| matches!(r.country, Remote::Loading)) => Remote::Loading,
+        true => Remote::Error,
+    };
+    (gender, country)
+}
+
+/// Internal helper function: record another edit to the groups.
+fn add_edit(edits: &UseState<Vec<Edit>>, edit: Edit) {
+    let mut updated = edits.get().clone();
+    updated.push(edit);
+    edits.set(updated);
+}
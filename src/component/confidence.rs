@@ -0,0 +1,18 @@
+//! Mini bar chart representing a probability
+
+use crate::prelude::*;
+
+// Render a confidence bar and label, for a given probability (clamped to the
+// valid range, in case of any rounding errors from the API)
+#[inline_props]
+pub fn ConfidenceBar(cx: Scope<'_>, probability: f32) -> Element<'_> {
+    let prob = f32::round(probability.clamp(0.0, 1.0) * 100.0) as u8;
+    let prob_str = if prob > 0 { format!("{prob}%") } else { String::from("") };
+    cx.render(rsx! {
+        div {
+            class: "confidence",
+            style: "width: {prob}%",
+            "{prob_str}"
+        }
+    })
+}
@@ -1,20 +1,29 @@
 //! "Copy to clipboard" button
 
+use crate::nicknames;
+use crate::overrides::Overrides;
 use crate::prelude::*;
+use crate::settings::LOCALE;
 
 // Component for the "Copy to clipboard" button
 #[tracing::instrument(skip_all)]
 #[inline_props]
-pub fn CopyButton(cx: Scope<'_>, names: UseState<Names>, db: UseRef<Db>) -> Element<'_> {
+pub fn CopyButton(
+    cx: Scope<'_>,
+    names: UseState<Names>,
+    db: UseRef<Db>,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
     // Persistent state: whether or not we are displaying the label saying how
     // many rows have just been copied
     let label = use_state(&cx, Option::<u16>::default);
+    let msgs = use_read(&cx, LOCALE).messages();
     // Transient derived state: button disabled or not
     let disabled = label.is_some() || names.is_empty();
 
     // Onclick handler: do the copy, display the label, and set a timer to remove it
     let onclick = move |_: MouseEvent| {
-        let num_rows = copy(names, db);
+        let num_rows = copy(names, db, overrides);
         label.set(Some(num_rows));
         spawn_local({
             to_owned!(label);
@@ -27,16 +36,20 @@ pub fn CopyButton(cx: Scope<'_>, names: UseState<Names>, db: UseRef<Db>) -> Elem
 
     // Optional label
     let label = match label.get() {
-        Some(num_rows) => rsx! {
-            span {
-                class: "copy-label",
-                "✓ Copied {num_rows} rows to clipboard"
+        Some(num_rows) => {
+            let copied = (msgs.copied)(*num_rows);
+            rsx! {
+                span {
+                    class: "copy-label",
+                    "{copied}"
+                }
             }
-        },
+        }
         _ => rsx! { "" },
     };
 
     // Overall view
+    let copy_button = msgs.copy_button;
     cx.render(rsx! {
         div {
             class: "copy-button",
@@ -45,23 +58,32 @@ pub fn CopyButton(cx: Scope<'_>, names: UseState<Names>, db: UseRef<Db>) -> Elem
                 class: "btn btn-outline-primary btn-sm",
                 disabled: "{disabled}",
                 onclick: onclick,
-                "Copy M/F column to clipboard"
+                "{copy_button}"
             }
         }
     })
 }
 
-// Copy the M/F column to the clipboard, and return the number of rows
+// Copy the M/F column to the clipboard, and return the number of rows. Any
+// manual overrides take precedence over the API results (which may be for the
+// full forms of nicknames, as shown in the table).
 #[tracing::instrument(skip_all)]
-fn copy(names: &UseState<Names>, db: &UseRef<Db>) -> u16 {
+fn copy(names: &UseState<Names>, db: &UseRef<Db>, overrides: &UseRef<Overrides>) -> u16 {
     // Assemble the plain text string.
     let mut num_rows = 0;
+    let db = db.read();
+    let overrides = overrides.read();
     let mf_col = names
         .iter()
         .map(|n| {
             num_rows += 1;
-            match &db.read().get(n).unwrap().gender {
-                Remote::Success(gender) => gender.summarised(),
+            if let Some(gender) = overrides.get(n).and_then(|o| o.gender) {
+                return gender.letter();
+            }
+            // Names still being typed may not have been queried yet.
+            let (results, _) = nicknames::results(&db, &overrides, n);
+            match results.map(|results| &results.gender) {
+                Some(Remote::Success(gender)) => gender.summarised(),
                 _ => "?",
             }
         })
@@ -69,17 +91,30 @@ fn copy(names: &UseState<Names>, db: &UseRef<Db>) -> u16 {
         .join("\n");
 
     // Copy the text to the clipboard
+    write_clipboard(&mf_col);
+
+    // Return the number of rows copied
+    num_rows
+}
+
+/// Internal helper function: write text to the clipboard. The clipboard API is
+/// still unstable in web-sys, so is only enabled for wasm builds (see
+/// .cargo/config.toml).
+#[cfg(target_arch = "wasm32")]
+fn write_clipboard(text: &str) {
     let clipboard = web_sys::window().unwrap().navigator().clipboard().unwrap();
     // The next line fails on Safari when developing with `trunk serve`, since
     // Safari requires a secure context to use this API. But it works fine in
     // production over https, and during development can be tested in Chrome.
-    let fut = JsFuture::from(clipboard.write_text(&mf_col));
+    let fut = JsFuture::from(clipboard.write_text(text));
     spawn_local(async move {
         if let Err(err) = fut.await {
             tracing::error!("Unable to copy to clipboard: {:?}", err);
         }
     });
-
-    // Return the number of rows copied
-    num_rows
 }
+
+/// Internal helper function: there's no clipboard natively (eg under `cargo
+/// test`).
+#[cfg(not(target_arch = "wasm32"))]
+fn write_clipboard(_text: &str) {}
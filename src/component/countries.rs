@@ -0,0 +1,108 @@
+//! Render the country results for one name
+
+use names_core::iso3166::{buckets, remainder, Bucket};
+
+use crate::prelude::*;
+use crate::settings::{GROUPING, INLINE_COUNTRIES, LOCALE};
+
+/// Remainders smaller than this would render as 0%, so aren't worth showing.
+const MIN_REMAINDER: f32 = 0.005;
+
+// Component to display all the country results for one name, grouped as per
+// the current setting. The most likely few are shown inline, with everything
+// else folded into an "other" bucket, and a toggle expands this to list every
+// country (or group) the API returned.
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn Countries(cx: Scope<'_>, countries: Vec<CountryResult>) -> Element<'_> {
+    let inline = *use_read(&cx, INLINE_COUNTRIES);
+    let grouping = *use_read(&cx, GROUPING);
+    let msgs = use_read(&cx, LOCALE).messages();
+    let expanded = use_state(&cx, || false);
+    let buckets = buckets(countries, grouping, msgs);
+    let (other_label, unaccounted_label) = (msgs.other, msgs.unaccounted);
+
+    // Everything not shown inline, including the probability mass that the API
+    // didn't attribute to any country at all.
+    let shown = buckets.len().min(inline);
+    let other = remainder(&buckets[..shown]);
+    let unaccounted = remainder(&buckets);
+
+    // Inline view
+    let inline_view = buckets[..shown].iter().map(bucket_view);
+    let other_view = (other >= MIN_REMAINDER).then(|| {
+        rsx! {
+            div {
+                class: "country other",
+                ConfidenceBar { probability: other }
+                "{other_label}"
+            }
+        }
+    });
+
+    // Expanded view, and the toggle for it. Don't bother offering the toggle if
+    // it would add nothing to what is already shown inline.
+    let all = &buckets;
+    let expanded_view = expanded.get().then(|| {
+        rsx! {
+            div {
+                class: "countries-all",
+                all.iter().map(bucket_view)
+                div {
+                    class: "country unaccounted",
+                    ConfidenceBar { probability: unaccounted }
+                    "{unaccounted_label}"
+                }
+            }
+        }
+    });
+    let toggle = (buckets.len() > shown || unaccounted >= MIN_REMAINDER).then(|| {
+        let label = if *expanded.get() { "▴" } else { "▾" };
+        let title = msgs.show_all_countries;
+        rsx! {
+            button {
+                class: "btn btn-link btn-sm expand",
+                title: "{title}",
+                onclick: move |ev: MouseEvent| {
+                    // Don't let the enclosing cell take this as a click to edit
+                    ev.cancel_bubble();
+                    expanded.set(!expanded.get());
+                },
+                "{label}"
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        div {
+            class: "countries",
+            inline_view
+            other_view
+            toggle
+        }
+        expanded_view
+    })
+}
+
+/// Keyed [BucketView] for one entry in a list of buckets.
+pub(super) fn bucket_view<'a>(bucket: &Bucket) -> LazyNodes<'a, '_> {
+    let label = &bucket.label;
+    rsx! { BucketView { key: "{label}", bucket: bucket.clone() } }
+}
+
+// Render a single country (or group of countries) with its confidence bar
+#[inline_props]
+fn BucketView(cx: Scope<'_>, bucket: Bucket) -> Element<'_> {
+    let flag = bucket.flag.as_deref().unwrap_or_default();
+    let label = &bucket.label;
+    let title = &bucket.title;
+    cx.render(rsx! {
+        div {
+            class: "country",
+            title: "{title}",
+            ConfidenceBar { probability: bucket.probability }
+            span { class: "flag", "{flag}" }
+            "{label}"
+        }
+    })
+}
@@ -0,0 +1,353 @@
+//! Page for a single name, at `/name/<name>`: the full gender result, every
+//! country, the gender in each of the user's chosen countries, and the age
+//! estimate, plus the raw API responses
+
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use names_core::iso3166;
+
+use super::table::RenderRemote;
+use crate::db::{self, Queue};
+use crate::prelude::*;
+use crate::route::{navigate, Route, ROUTE};
+use crate::settings::LOCALE;
+
+/// Local storage key for the countries to break the gender down by
+const COUNTRIES_STORAGE_KEY: &str = "detail_countries";
+
+/// Number of the name's own top countries to break the gender down by, until
+/// the user chooses some
+const DEFAULT_COUNTRIES: usize = 3;
+
+/// Everything looked up for the name
+#[derive(Default)]
+struct Lookups {
+    gender: Remote<Detailed<GenderResult>>,
+    countries: Remote<Detailed<Vec<CountryResult>>>,
+    age: Remote<Detailed<AgeResult>>,
+    /// Gender localised to each chosen country, by ISO 3166 alpha-2 code
+    localised: BTreeMap<String, Remote<Detailed<GenderResult>>>,
+}
+
+/// One lookup for the page for a single name, sent via the [Queue] like every
+/// other request, with its result stored in the page's [Lookups]
+#[derive(Clone)]
+pub struct DetailLookup {
+    name: Name,
+    kind: Kind,
+    lookups: UseRef<Lookups>,
+}
+
+/// Which lookup
+#[derive(Clone)]
+enum Kind {
+    Gender,
+    Countries,
+    Age,
+    /// Gender localised to the country with this ISO 3166 alpha-2 code
+    Localised(String),
+}
+
+impl DetailLookup {
+    /// The name being looked up
+    pub fn name(&self) -> &Name { &self.name }
+
+    /// Send the request, storing the result if it succeeds.
+    pub async fn fetch(&self, client: &WebClient<'_>) -> Result<(), ApiError> {
+        let (config, name) = (api_config(), &self.name);
+        match &self.kind {
+            Kind::Gender => {
+                let result = get_gender_detail(client, &config, name, None).await?;
+                self.lookups.write().gender = Remote::Success(result);
+            }
+            Kind::Countries => {
+                let result = get_countries_detail(client, &config, name).await?;
+                self.lookups.write().countries = Remote::Success(result);
+            }
+            Kind::Age => {
+                let result = get_age(client, &config, name).await?;
+                self.lookups.write().age = Remote::Success(result);
+            }
+            Kind::Localised(code) => {
+                let result = get_gender_detail(client, &config, name, Some(code)).await?;
+                self.lookups
+                    .write()
+                    .localised
+                    .insert(code.clone(), Remote::Success(result));
+            }
+        }
+        Ok(())
+    }
+
+    /// Note that the request failed.
+    pub fn failed(&self) {
+        let mut lookups = self.lookups.write();
+        match &self.kind {
+            Kind::Gender => lookups.gender = Remote::Error,
+            Kind::Countries => lookups.countries = Remote::Error,
+            Kind::Age => lookups.age = Remote::Error,
+            Kind::Localised(code) => {
+                lookups.localised.insert(code.clone(), Remote::Error);
+            }
+        }
+    }
+}
+
+// Component for the page for a single name. Everything is looked up afresh,
+// rather than taken from the main table, to get the full responses. Needs the
+// [Db] and [Queue], to send the lookups along with everything else.
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn Detail(
+    cx: Scope<'_>,
+    name: Name,
+    db: UseRef<Db>,
+    queue: UseRef<Queue>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+    let set_route = use_set(&cx, ROUTE);
+    let lookups = use_ref(&cx, Lookups::default);
+    let chosen = use_ref(&cx, || {
+        let chosen: Option<Vec<String>> = LocalStorage::get(COUNTRIES_STORAGE_KEY).ok();
+        chosen
+    });
+    let lookup =
+        |kind| DetailLookup { name: name.clone(), kind, lookups: lookups.clone() };
+
+    // Start the lookups for the name itself, first time through.
+    cx.use_hook(|_| {
+        db::look_up_detail(
+            db,
+            queue,
+            [Kind::Gender, Kind::Countries, Kind::Age].map(lookup),
+        );
+    });
+
+    // The countries to break the gender down by: the user's choice, or
+    // otherwise the name's own top countries. Look up any new ones.
+    let countries: Vec<String> = match &*chosen.read() {
+        Some(chosen) => chosen.clone(),
+        None => match &lookups.read().countries {
+            Remote::Success(countries) => countries
+                .result
+                .iter()
+                .take(DEFAULT_COUNTRIES)
+                .map(|c| c.country.clone())
+                .collect(),
+            _ => Vec::new(),
+        },
+    };
+    let new: Vec<String> = countries
+        .iter()
+        .filter(|c| !lookups.read().localised.contains_key(*c))
+        .cloned()
+        .collect();
+    if !new.is_empty() {
+        for code in new.iter() {
+            lookups.write_silent().localised.insert(code.clone(), Remote::Loading);
+        }
+        db::look_up_detail(db, queue, new.into_iter().map(Kind::Localised).map(lookup));
+    }
+
+    // Gender and countries
+    let lookups = lookups.read();
+    let gender = lookups
+        .gender
+        .render(msgs, |d| rsx! { GenderSummary { result: d.result.clone() } });
+    let country_rows = lookups.countries.render(msgs, |d| {
+        let rows = d.result.iter().map(|r| {
+            let code = &r.country;
+            let country = iso3166::country(code);
+            let label = country.map_or(code.as_str(), msgs.country);
+            let flag = iso3166::flag(code).unwrap_or_default();
+            let region = match country {
+                Some(c) => {
+                    c.sub_region.map_or((msgs.continent)(c.continent), msgs.sub_region)
+                }
+                None => msgs.unknown,
+            };
+            rsx! {
+                tr {
+                    key: "{code}",
+                    td {
+                        span { class: "flag", "{flag}" }
+                        "{label}"
+                    }
+                    td { class: "region", "{region}" }
+                    td { ConfidenceBar { probability: r.probability } }
+                }
+            }
+        });
+        rsx! {
+            table {
+                class: "table table-sm table-bordered",
+                rows
+            }
+        }
+    });
+
+    // Gender in each chosen country, and a choice of more
+    let localised_rows = countries.iter().map(|code| {
+        let label = iso3166::country(code).map_or(code.as_str(), msgs.country);
+        let flag = iso3166::flag(code).unwrap_or_default();
+        let result = lookups.localised.get(code).unwrap_or(&Remote::Loading);
+        let gender =
+            result.render(msgs, |d| rsx! { GenderSummary { result: d.result.clone() } });
+        let remaining: Vec<String> =
+            countries.iter().filter(|c| *c != code).cloned().collect();
+        let remove_country = msgs.remove_country;
+        rsx! {
+            tr {
+                key: "{code}",
+                td {
+                    span { class: "flag", "{flag}" }
+                    "{label}"
+                }
+                td { gender }
+                td {
+                    button {
+                        class: "close",
+                        title: "{remove_country}",
+                        onclick: move |_| choose(chosen, remaining.clone()),
+                        "×"
+                    }
+                }
+            }
+        }
+    });
+    let mut others: Vec<_> = iso3166::all()
+        .filter(|c| !countries.iter().any(|code| code == c.alpha2))
+        .map(|c| (c.alpha2, (msgs.country)(c)))
+        .collect();
+    others.sort_by_key(|&(_, label)| label);
+    let add_country = msgs.add_country;
+    let current = countries.clone();
+    let onchange = move |ev: FormEvent| {
+        if !ev.value.is_empty() {
+            let mut updated = current.clone();
+            updated.push(ev.value.clone());
+            choose(chosen, updated);
+        }
+    };
+
+    // Age
+    let age = lookups.age.render(msgs, |d| {
+        let estimate = match d.result.age {
+            Some(age) => {
+                format!("{}, {}", (msgs.age_estimate)(age), (msgs.people)(d.result.count))
+            }
+            None => msgs.no_age.to_owned(),
+        };
+        rsx! { "{estimate}" }
+    });
+
+    // Raw responses, for debugging
+    let localised = lookups
+        .localised
+        .iter()
+        .map(|(code, result)| (format!("{} ({code})", msgs.detail_gender), json(result)));
+    let raw = [
+        (msgs.detail_gender.to_owned(), json(&lookups.gender)),
+        (msgs.detail_countries.to_owned(), json(&lookups.countries)),
+        (msgs.detail_age.to_owned(), json(&lookups.age)),
+    ]
+    .into_iter()
+    .chain(localised)
+    .filter_map(|(label, json)| Some((label, json?)))
+    .map(|(label, json)| {
+        rsx! {
+            div {
+                key: "{label}",
+                h6 { "{label}" }
+                pre { "{json}" }
+            }
+        }
+    });
+
+    let (back, raw_json) = (msgs.back_to_list, msgs.raw_json);
+    let (detail_gender, detail_countries, localised_heading, detail_age) = (
+        msgs.detail_gender,
+        msgs.detail_countries,
+        msgs.localised_heading,
+        msgs.detail_age,
+    );
+    cx.render(rsx! {
+        div {
+            class: "detail",
+            a {
+                href: "/",
+                prevent_default: "onclick",
+                onclick: move |_| navigate(set_route, Route::Main),
+                "{back}"
+            }
+            h3 { "{name}" }
+            h5 { "{detail_gender}" }
+            div { class: "gender", gender }
+            h5 { "{detail_countries}" }
+            country_rows
+            h5 { "{localised_heading}" }
+            table {
+                class: "table table-sm table-bordered localised",
+                localised_rows
+            }
+            select {
+                class: "form-control form-control-sm add-country",
+                value: "",
+                onchange: onchange,
+                option { value: "", "{add_country}" }
+                others.iter().map(|(code, label)| rsx! {
+                    option { key: "{code}", value: "{code}", "{label}" }
+                })
+            }
+            h5 { "{detail_age}" }
+            div {
+                class: "age",
+                age
+                " ("
+                a { href: "https://agify.io", "agify.io" }
+                ")"
+            }
+            details {
+                class: "raw-json",
+                summary { "{raw_json}" }
+                raw
+            }
+        }
+    })
+}
+
+// A gender result in full: the probability, how many people it's based on,
+// and the 95% confidence interval that gives
+#[inline_props]
+fn GenderSummary(cx: Scope<'_>, result: GenderResult) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+    let label = result.summarised();
+    let people = (msgs.people)(result.count);
+    let interval = result.interval().map(|(low, high)| (msgs.interval)(low, high));
+    cx.render(rsx! {
+        ConfidenceBar { probability: result.probability }
+        "{label} "
+        span { class: "count", "({people})" }
+        interval.map(|interval| rsx! {
+            span { class: "interval", "{interval}" }
+        })
+    })
+}
+
+/// Internal helper function: the raw JSON response, once there is one.
+fn json<T>(remote: &Remote<Detailed<T>>) -> Option<&str> {
+    match remote {
+        Remote::Success(detailed) => Some(&detailed.json),
+        _ => None,
+    }
+}
+
+/// Internal helper function: change the countries to break the gender down by,
+/// and remember them for next time.
+fn choose(chosen: &UseRef<Option<Vec<String>>>, countries: Vec<String>) {
+    if let Err(err) = LocalStorage::set(COUNTRIES_STORAGE_KEY, &countries) {
+        tracing::error!("Unable to save countries: {:?}", err);
+    }
+    *chosen.write() = Some(countries);
+}
@@ -1,31 +1,68 @@
 //! Text entry box
 
-use unidecode::unidecode;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use names_core::normalise::{parse_line, split_lines};
 
 use crate::prelude::*;
+use crate::settings::LOCALE;
 
-/// Name to ignore, so if this is the column title in a source spreadsheet,
-/// where the entire column is pasted into the input textarea, we strip it out
-/// of the query. Sorry if your name is "First".
-const IGNORED_NAME: &str = "First";
+/// Number of milliseconds after the user stops typing before the names are
+/// committed for querying.
+const DEBOUNCE_TIME: u32 = 600;
 
 // @@@ /// doesn't work here
 
 // Entry component: textarea for entering of names, and update a vec of ASCII
-// names in response.
+// names in response. The `names` update immediately, for display, but
+// `committed` (which is what gets sent to the APIs) waits until the user
+// pauses typing, finishes a line, or leaves the textarea, so we don't burn
+// quota on "Al", "Alex", "Alexa" etc on the way to "Alexander".
 #[tracing::instrument(skip_all)]
 #[inline_props]
-pub fn Entry(cx: Scope, names: UseState<Names>) -> Element<'_> {
+pub fn Entry(
+    cx: Scope,
+    names: UseState<Names>,
+    committed: UseState<Names>,
+) -> Element<'_> {
+    let placeholder = use_read(&cx, LOCALE).messages().placeholder;
+
+    // Persistent state: the normalisation cache, and a generation counter
+    // bumped on every edit, so a pending debounced commit can tell if it has
+    // been superseded.
+    let cache: &RefCell<LineCache> = cx.use_hook(|_| RefCell::new(LineCache::default()));
+    let generation: &Rc<Cell<u32>> = cx.use_hook(|_| Rc::new(Cell::new(0)));
+
     // Handle updates
-    let oninput = |ev: dioxus::events::FormEvent| {
-        let names_vec: Vec<_> = ev
-            .value
-            .split('\n')
-            .map(str::trim)
-            .filter(|&s| !s.is_empty() && s != IGNORED_NAME)
-            .map(normalise_name)
-            .collect();
-        names.set(names_vec);
+    let oninput = move |ev: dioxus::events::FormEvent| {
+        let (names_vec, lines_changed, newline_added) =
+            cache.borrow_mut().parse(&ev.value);
+        if *names.current() != names_vec {
+            names.set(names_vec.clone());
+        }
+
+        // Commit straight away if the user has just finished a line (by adding a
+        // newline, rather than the text merely ending with one), or pasted or
+        // deleted several at once, and otherwise once they pause.
+        let this_generation = generation.get().wrapping_add(1);
+        generation.set(this_generation);
+        if newline_added || lines_changed > 1 {
+            commit(committed, names_vec);
+        } else {
+            to_owned![committed, generation];
+            spawn_local(async move {
+                TimeoutFuture::new(DEBOUNCE_TIME).await;
+                if generation.get() == this_generation {
+                    commit(&committed, names_vec);
+                }
+            });
+        }
+    };
+    let onblur = move |_: dioxus::events::FocusEvent| {
+        generation.set(generation.get().wrapping_add(1));
+        commit(committed, names.current().to_vec());
     };
 
     cx.render(rsx! {
@@ -34,18 +71,58 @@ pub fn Entry(cx: Scope, names: UseState<Names>) -> Element<'_> {
         // so in practice the textarea is always stretched to be taller
         // than its raw contents by the adjacent table.
         textarea {
-            autofocus: "true", cols: "15", placeholder: "First names...",
-            oninput: oninput
+            autofocus: "true", cols: "15", placeholder: "{placeholder}",
+            oninput: oninput,
+            onblur: onblur
         }
     })
 }
 
-/// Normalise a name to a single ASCII word (all the API seems to handle).
-fn normalise_name(name: &str) -> Name {
-    let ascii_name = unidecode(name);
-    let first_word = match ascii_name.split_once(|c: char| !c.is_alphabetic()) {
-        Some((first_word, _rest)) => first_word.to_owned(),
-        None => ascii_name,
-    };
-    KString::from_string(first_word)
+/// Commit names for querying, if they have changed.
+fn commit(committed: &UseState<Names>, names: Names) {
+    if *committed.current() != names {
+        committed.set(names);
+    }
+}
+
+/// The normalised name (or [None] if ignored) for each distinct line seen
+/// recently, so unchanged lines don't get re-normalised on every keystroke.
+#[derive(Default)]
+struct LineCache {
+    names: HashMap<String, Option<Name>>,
+    num_lines: usize,
+    num_newlines: usize,
+}
+
+impl LineCache {
+    /// Parse the textarea contents into names, also returning how many lines
+    /// have been added or removed since last time, and whether a newline has
+    /// been added.
+    fn parse(&mut self, text: &str) -> (Names, usize, bool) {
+        let lines: Vec<&str> = split_lines(text).map(str::trim).collect();
+        let names = lines
+            .iter()
+            .filter_map(|&line| {
+                if let Some(name) = self.names.get(line) {
+                    return name.clone();
+                }
+                let name = parse_line(line);
+                self.names.insert(line.to_owned(), name.clone());
+                name
+            })
+            .collect();
+
+        // Stop the cache growing without bound as lines get edited.
+        if self.names.len() > 2 * lines.len() + 100 {
+            let current: HashSet<&str> = lines.iter().copied().collect();
+            self.names.retain(|line, _| current.contains(line.as_str()));
+        }
+
+        let lines_changed = lines.len().abs_diff(self.num_lines);
+        self.num_lines = lines.len();
+        let num_newlines = text.matches('\n').count();
+        let newline_added = num_newlines > self.num_newlines;
+        self.num_newlines = num_newlines;
+        (names, lines_changed, newline_added)
+    }
 }
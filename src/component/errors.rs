@@ -5,44 +5,63 @@ use std::lazy::SyncOnceCell;
 use futures::stream::StreamExt;
 use im_rc::OrdMap;
 
+use crate::db::{self, Queue};
 use crate::prelude::*;
+use crate::settings::LOCALE;
 
 /// Internal identifier for one error message
 type Id = i32;
 
+/// Maximum number of affected names to list in an error's tooltip
+const MAX_NAMES_SHOWN: usize = 20;
+
 /// Number of milliseconds after which an error message is automatically
-/// removed.
+/// removed (unless it needs the user to do something), counting from the most
+/// recent repeat.
 const AUTO_REMOVAL_TIME: u32 = 10_000;
 
+/// One displayed error, which may have been reported several times
+#[derive(Debug, Clone)]
+struct Entry {
+    error: AppError,
+    /// Number of times reported
+    count: u32,
+}
+
 /// Event loop messages
 #[derive(Debug)]
 enum Msg {
-    Add(ErrMsg),
+    Add(AppError),
     Remove(Id),
 }
 
 /// Static handle to the event loop coroutine
 static CORO: SyncOnceCell<CoroutineHandle<Msg>> = SyncOnceCell::new();
 
-/// Public function for adding a global error message
-pub fn add_err_msg(err_msg: ErrMsg) {
+/// Public function for adding a global error
+pub fn add_error(error: AppError) {
     if let Some(coro) = CORO.get() {
-        coro.send(Msg::Add(err_msg));
+        coro.send(Msg::Add(error));
     } else {
-        tracing::error!("add_err_msg called with uninitialized coro");
+        tracing::error!("add_error called with uninitialized coro");
     }
 }
 
-/// Error message component
+// Error message component. Needs the [Db] and [Queue], so that any failed
+// lookups can be retried.
 #[tracing::instrument(skip_all)]
-pub fn Errors(cx: Scope<'_>) -> Element<'_> {
-    // The internal data structure is an [OrdMap] from [Id]s to [ErrMsg]s. The
+#[inline_props]
+pub fn Errors(cx: Scope<'_>, db: UseRef<Db>, queue: UseRef<Queue>) -> Element<'_> {
+    // The internal data structure is an [OrdMap] from [Id]s to [Entry]s. The
     // [Id] enables individual error messages to be deleted, and using a
     // [OrdMap] rather than a HashMap means that iterating the structure
     // preserves insertion order, since the [Id] keys monotonically increase
     // over time.
     let next_id = use_state(&cx, Id::default);
-    let err_map = use_ref(&cx, OrdMap::<Id, ErrMsg>::default);
+    let err_map = use_ref(&cx, OrdMap::<Id, Entry>::default);
+    // Any API key being entered, in response to an error
+    let key_input = use_state(&cx, || api_key().unwrap_or_default());
+    let msgs = use_read(&cx, LOCALE).messages();
 
     // Event loop
     let coro = use_coroutine(&cx, |mut rx| {
@@ -50,16 +69,47 @@ pub fn Errors(cx: Scope<'_>) -> Element<'_> {
         async move {
             while let Some(msg) = rx.next().await {
                 match msg {
-                    Msg::Add(err_msg) => {
-                        let id = *next_id.current();
-                        next_id += 1;
-                        err_map.write().insert(id, err_msg);
-
-                        to_owned![err_map];
-                        spawn_local(async move {
-                            TimeoutFuture::new(AUTO_REMOVAL_TIME).await;
-                            err_map.write().remove(&id);
-                        });
+                    Msg::Add(error) => {
+                        // Fold any repeat of an error already shown into that
+                        // one, which then starts its removal timer afresh.
+                        let repeat_of = err_map
+                            .read()
+                            .iter()
+                            .find(|(_, entry)| entry.error.is_repeat_of(&error))
+                            .map(|(&id, entry)| (id, entry.count));
+                        let sticky = error.needs_user_action();
+                        let (id, count) = match repeat_of {
+                            Some((id, count)) => {
+                                if let Some(entry) = err_map.write().get_mut(&id) {
+                                    entry.error.merge_names(error.names);
+                                    entry.count += 1;
+                                }
+                                (id, count + 1)
+                            }
+                            None => {
+                                let id = *next_id.current();
+                                next_id += 1;
+                                err_map.write().insert(id, Entry { error, count: 1 });
+                                (id, 1)
+                            }
+                        };
+
+                        // Only remove it if there have been no repeats since.
+                        // Without a browser (eg under `cargo test`), there's
+                        // no timer to do that with, so it just stays.
+                        if !sticky && window().is_some() {
+                            to_owned![err_map];
+                            spawn_local(async move {
+                                TimeoutFuture::new(AUTO_REMOVAL_TIME).await;
+                                let unrepeated = matches!(
+                                    err_map.read().get(&id),
+                                    Some(entry) if entry.count == count
+                                );
+                                if unrepeated {
+                                    err_map.write().remove(&id);
+                                }
+                            });
+                        }
                     }
 
                     Msg::Remove(id) => {
@@ -79,11 +129,92 @@ pub fn Errors(cx: Scope<'_>) -> Element<'_> {
     cx.render(rsx! {
         div {
             class: "errors",
-            err_map.read().iter().map(|(&id, err_msg)| {
+            err_map.read().iter().map(|(&id, Entry { error, count })| {
+                let class = error.severity.alert_class();
+                let service = match error.service {
+                    Some(service) => format!("{}: ", (msgs.service)(service)),
+                    None => String::new(),
+                };
+                let message = match &error.problem {
+                    Problem::Api(err) => (msgs.api_error)(err),
+                    Problem::Notice(notice) => (msgs.notice)(*notice).to_owned(),
+                };
+                let message = match count {
+                    1 => message,
+                    count => format!("{message} (×{count})"),
+                };
+
+                // Summarise the affected names, listing (some of) them in a
+                // tooltip.
+                let (num_names, names) = (error.names.len(), error.names.clone());
+                let affected = match num_names {
+                    0 => String::new(),
+                    n => (msgs.affected_names)(n),
+                };
+                let mut tooltip = names
+                    .iter()
+                    .take(MAX_NAMES_SHOWN)
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if num_names > MAX_NAMES_SHOWN {
+                    tooltip.push_str(", …");
+                }
+
+                // Retry the affected names (if any), and remove the error.
+                let retry = {
+                    let service = error.service;
+                    move || {
+                        if let Some(service) = service {
+                            db::retry(db, queue, service, &names);
+                        }
+                        coro.send(Msg::Remove(id));
+                    }
+                };
+                let actions = error.actions.iter().map(move |&action| {
+                    let retry = retry.clone();
+                    match action {
+                        Action::Retry => {
+                            let label = msgs.retry;
+                            rsx! {
+                                button {
+                                    class: "btn btn-sm btn-outline-dark",
+                                    onclick: move |_| retry(),
+                                    "{label}"
+                                }
+                            }
+                        }
+                        Action::EnterApiKey => {
+                            let (placeholder, label) = (msgs.api_key, msgs.save_api_key);
+                            let value = key_input.get().clone();
+                            rsx! {
+                                input {
+                                    class: "form-control form-control-sm api-key",
+                                    placeholder: "{placeholder}",
+                                    value: "{value}",
+                                    oninput: move |ev: FormEvent| key_input.set(ev.value.clone())
+                                }
+                                button {
+                                    class: "btn btn-sm btn-outline-dark",
+                                    onclick: move |_| {
+                                        set_api_key(key_input);
+                                        retry();
+                                    },
+                                    "{label}"
+                                }
+                            }
+                        }
+                    }
+                });
+
                 rsx! {
                     div {
                         key: "{id}",
-                        class: "alert alert-danger", "{err_msg}"
+                        class: "alert {class}",
+                        strong { "{service}" }
+                        "{message} "
+                        span { class: "affected", title: "{tooltip}", "{affected}" }
+                        div { class: "actions", actions }
                         button {
                             class: "close",
                             onclick: move |_| coro.send(Msg::Remove(id)),
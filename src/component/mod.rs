@@ -1,13 +1,31 @@
 //! Components
 
 #![allow(non_snake_case)]
+mod clusters;
+mod confidence;
 mod copy;
+mod countries;
+mod detail;
 mod entry;
 mod errors;
+mod overrides;
+mod queue;
+mod settings;
+mod summary;
 mod table;
+#[cfg(test)]
+mod tests;
 
 // Re-export
+pub use clusters::Clusters;
+pub use confidence::ConfidenceBar;
 pub use copy::CopyButton;
+pub use countries::Countries;
+pub use detail::{Detail, DetailLookup};
 pub use entry::Entry;
-pub use errors::{add_err_msg, Errors};
+pub use errors::{add_error, Errors};
+pub use overrides::{CountryCell, GenderCell, NameCell, NoteCell};
+pub use queue::QueueStatus;
+pub use settings::Settings;
+pub use summary::Summary;
 pub use table::Table;
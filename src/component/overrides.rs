@@ -0,0 +1,267 @@
+//! Table cells the user can click on to override the API results, plus the
+//! name cell (with any nickname's full form) and the free-text note cell. The
+//! current override is passed in as a prop, rather than read from [Overrides],
+//! so these are only re-rendered when it changes.
+
+use names_core::{iso3166, nicknames};
+
+use super::table::RenderRemote;
+use crate::i18n::Messages;
+use crate::overrides::{Override, Overrides};
+use crate::prelude::*;
+use crate::route::{navigate, Route, ROUTE};
+use crate::settings::LOCALE;
+
+// Name cell: the name, linking to its own page, and if it's a nickname, its
+// full form, marked to show whether the results are the full form's. Clicking
+// on the full form opts out of looking it up (or back in again).
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn NameCell(
+    cx: Scope<'_>,
+    name: Name,
+    via_full_form: bool,
+    skip_full_form: bool,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+    let set_route = use_set(&cx, ROUTE);
+    let route = Route::Name(name.clone());
+    let path = route.path();
+    let link = rsx! {
+        a {
+            href: "{path}",
+            prevent_default: "onclick",
+            onclick: move |_| navigate(set_route, route.clone()),
+            "{name}"
+        }
+    };
+    let full = match nicknames::full_form(name) {
+        Some(full) => full,
+        None => return cx.render(rsx! { td { link } }),
+    };
+    let (class, title) = match (*skip_full_form, *via_full_form) {
+        (true, _) => ("badge-light skipped", (msgs.full_form_skipped)(name, full)),
+        (false, true) => ("badge-info", (msgs.full_form_used)(name, full)),
+        (false, false) => ("badge-light", (msgs.full_form_checked)(name, full)),
+    };
+    let skip = !*skip_full_form;
+    cx.render(rsx! {
+        td {
+            link
+            button {
+                class: "badge badge-pill full-form {class}",
+                title: "{title}",
+                onclick: move |_| overrides.write().update(name, |o| o.skip_full_form = skip),
+                "→ {full}"
+            }
+        }
+    })
+}
+
+// Gender cell: the API result, unless the user has overridden it. The whole
+// override is passed in, since dioxus turns an `Option` prop into an optional
+// one, which can't then be given a `None`.
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn GenderCell(
+    cx: Scope<'_>,
+    name: Name,
+    gender: Remote<GenderResult>,
+    manual: Override,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+    let editing = use_state(&cx, || false);
+    let manual = manual.gender;
+
+    // Editing: choose between female, male, or reverting to the API result
+    if *editing.get() {
+        let current = manual.map(Gender::key).unwrap_or_default();
+        let use_api_result = msgs.use_api_result;
+        let onchange = move |ev: FormEvent| {
+            overrides.write().update(name, |o| o.gender = Gender::from_key(&ev.value));
+            editing.set(false);
+        };
+        return cx.render(rsx! {
+            td {
+                class: "editing",
+                select {
+                    class: "form-control form-control-sm",
+                    value: "{current}",
+                    onchange: onchange,
+                    option { value: "", "{use_api_result}" }
+                    option { value: "female", "F" }
+                    option { value: "male", "M" }
+                }
+                CancelButton { editing: editing.clone() }
+            }
+        });
+    }
+
+    // Otherwise, the override, or the API result
+    let contents = match manual {
+        Some(gender) => {
+            let label = gender.letter();
+            rsx! {
+                ConfidenceBar { probability: 1.0 }
+                "{label} ✎"
+            }
+        }
+        None => {
+            let offline = matches!(gender, Remote::Success(r) if r.offline);
+            let (badge, badge_title) =
+                (msgs.offline_estimate, msgs.offline_estimate_title);
+            gender.render(msgs, |r| {
+                let label = r.summarised();
+                rsx! {
+                    ConfidenceBar { probability: r.probability }
+                    "{label}"
+                    if offline {
+                        Some(rsx! {
+                            span {
+                                class: "badge badge-pill badge-secondary offline-estimate",
+                                title: "{badge_title}",
+                                "{badge}"
+                            }
+                        })
+                    } else {
+                        None
+                    }
+                }
+            })
+        }
+    };
+    let (class, title) = editable(manual.is_some(), msgs);
+    cx.render(rsx! {
+        td {
+            class: "{class}",
+            title: "{title}",
+            onclick: move |_| editing.set(true),
+            contents
+        }
+    })
+}
+
+// Country cell: the API results, unless the user has overridden them with a
+// single definite country
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn CountryCell(
+    cx: Scope<'_>,
+    name: Name,
+    country: Remote<Vec<CountryResult>>,
+    manual: Override,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+    let editing = use_state(&cx, || false);
+    let manual = &manual.country;
+
+    // Editing: choose any country, or revert to the API result
+    if *editing.get() {
+        let current = manual.as_deref().unwrap_or_default();
+        let use_api_result = msgs.use_api_result;
+        let mut countries: Vec<_> =
+            iso3166::all().map(|c| (c.alpha2, (msgs.country)(c))).collect();
+        countries.sort_by_key(|&(_, name)| name);
+        let onchange = move |ev: FormEvent| {
+            let country = (!ev.value.is_empty()).then(|| ev.value.clone());
+            overrides.write().update(name, |o| o.country = country);
+            editing.set(false);
+        };
+        return cx.render(rsx! {
+            td {
+                class: "editing",
+                select {
+                    class: "form-control form-control-sm",
+                    value: "{current}",
+                    onchange: onchange,
+                    option { value: "", "{use_api_result}" }
+                    countries.iter().map(|(code, label)| rsx! {
+                        option { key: "{code}", value: "{code}", "{label}" }
+                    })
+                }
+                CancelButton { editing: editing.clone() }
+            }
+        });
+    }
+
+    // Otherwise, the override (as a single certain country), or the API result
+    let contents = match manual {
+        Some(code) => {
+            let label = iso3166::country(code).map_or(code.as_str(), msgs.country);
+            let flag = iso3166::flag(code).unwrap_or_default();
+            rsx! {
+                div {
+                    class: "countries",
+                    div {
+                        class: "country",
+                        ConfidenceBar { probability: 1.0 }
+                        span { class: "flag", "{flag}" }
+                        "{label} ✎"
+                    }
+                }
+            }
+        }
+        None => country.render(msgs, |r| rsx! { Countries { countries: r.clone() } }),
+    };
+    let (class, title) = editable(manual.is_some(), msgs);
+    cx.render(rsx! {
+        td {
+            class: "{class}",
+            title: "{title}",
+            onclick: move |_| editing.set(true),
+            contents
+        }
+    })
+}
+
+// Free-text note for one name. This is kept up to date as the user types, but
+// only saved once they've finished (when the input loses focus).
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn NoteCell(
+    cx: Scope<'_>,
+    name: Name,
+    note: String,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let placeholder = use_read(&cx, LOCALE).messages().note_placeholder;
+    cx.render(rsx! {
+        td {
+            input {
+                class: "form-control form-control-sm note",
+                placeholder: "{placeholder}",
+                value: "{note}",
+                oninput: move |ev: FormEvent| {
+                    overrides.write().edit(name, |o| o.note = ev.value.clone());
+                },
+                onchange: move |_| overrides.read().save()
+            }
+        }
+    })
+}
+
+// Button to abandon editing a cell
+#[inline_props]
+fn CancelButton(cx: Scope<'_>, editing: UseState<bool>) -> Element<'_> {
+    let cancel = use_read(&cx, LOCALE).messages().cancel;
+    cx.render(rsx! {
+        button {
+            class: "close",
+            title: "{cancel}",
+            onclick: move |_| editing.set(false),
+            "×"
+        }
+    })
+}
+
+/// CSS class and tooltip for a cell that can be clicked on to edit it,
+/// depending on whether it currently holds a manual override.
+fn editable(manual: bool, msgs: &Messages) -> (&'static str, &'static str) {
+    match manual {
+        true => ("editable override", msgs.manual_override),
+        false => ("editable", msgs.click_to_override),
+    }
+}
@@ -0,0 +1,126 @@
+//! Overall progress of the API request queue
+
+use crate::db::{self, Queue};
+use crate::prelude::*;
+use crate::settings::LOCALE;
+
+/// Number of names in each state, for one service
+#[derive(Default)]
+struct Counts {
+    resolved: usize,
+    pending: usize,
+    failed: usize,
+}
+
+impl Counts {
+    /// Count one name's result
+    fn add<T>(&mut self, remote: Option<&Remote<T>>) {
+        match remote {
+            Some(Remote::Success(_)) => self.resolved += 1,
+            Some(Remote::Error) => self.failed += 1,
+            // Names still being typed may not have been queued yet.
+            Some(Remote::Loading) | None => self.pending += 1,
+        }
+    }
+
+    /// Percentages resolved and failed, for the progress bar
+    fn percentages(&self) -> (f32, f32) {
+        let total = (self.resolved + self.pending + self.failed).max(1) as f32;
+        (self.resolved as f32 * 100.0 / total, self.failed as f32 * 100.0 / total)
+    }
+}
+
+// Component showing a progress bar per service while there are names left to
+// look up, with how many are queued or in flight, an estimate of the time
+// remaining (or why we're waiting), and a button to pause or resume sending
+// requests. Also notes how many lookups were skipped (and so didn't count
+// against the daily quota), since the names were deleted before being sent.
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn QueueStatus(
+    cx: Scope<'_>,
+    names: UseState<Names>,
+    db: UseRef<Db>,
+    queue: UseRef<Queue>,
+) -> Element<'_> {
+    let msgs = use_read(&cx, LOCALE).messages();
+
+    // Tally up the results for the names entered.
+    let (mut gender, mut country) = (Counts::default(), Counts::default());
+    {
+        let db = db.read();
+        for name in names.iter() {
+            let results = db.get(name);
+            gender.add(results.map(|r| &r.gender));
+            country.add(results.map(|r| &r.country));
+        }
+    }
+
+    let queue_ref = queue.read();
+    let (paused, offline) = (queue_ref.paused(), queue_ref.offline());
+    let saved = match queue_ref.saved {
+        0 => String::new(),
+        saved => (msgs.saved_lookups)(saved),
+    };
+    if gender.pending + country.pending == 0 && !paused && !offline {
+        return cx.render(rsx! {
+            div { class: "queue-status", "{saved}" }
+        });
+    }
+
+    // One progress bar per service
+    let services = [(Service::Gender, gender), (Service::Country, country)]
+        .into_iter()
+        .map(|(service, counts)| {
+            let scheduler = queue_ref.scheduler(service);
+            let label = (msgs.service)(service);
+            let (resolved, failed) = counts.percentages();
+            let progress = (msgs.progress)(counts.resolved, counts.pending, counts.failed);
+            let depth = (msgs.queue_depth)(scheduler.queued(), scheduler.in_flight());
+            let backing_off = if scheduler.backing_off() { msgs.backing_off } else { "" };
+            rsx! {
+                div {
+                    key: "{label}",
+                    class: "service",
+                    strong { "{label}" }
+                    div {
+                        class: "progress",
+                        div { class: "progress-bar bg-success", style: "width: {resolved}%" }
+                        div { class: "progress-bar bg-danger", style: "width: {failed}%" }
+                    }
+                    "{progress} "
+                    span { class: "depth", "({depth}) " }
+                    span { class: "backing-off", "{backing_off}" }
+                }
+            }
+        });
+
+    // The estimate is for whichever service will take longest.
+    let eta = match (paused, Service::ALL.map(|s| queue_ref.scheduler(s).eta())) {
+        (true, _) => msgs.paused.to_owned(),
+        _ if offline => msgs.offline.to_owned(),
+        (false, [Some(gender), Some(country)]) => {
+            (msgs.eta)((gender.max(country) / 60_000.0).round() as u32)
+        }
+        _ => String::new(),
+    };
+    let button = if paused { msgs.resume } else { msgs.pause };
+    let onclick = move |_: MouseEvent| db::set_paused(db, queue, !paused);
+
+    cx.render(rsx! {
+        div {
+            class: "queue-status",
+            services
+            div {
+                class: "eta",
+                "{eta}"
+                button {
+                    class: "btn btn-outline-secondary btn-sm",
+                    onclick: onclick,
+                    "{button}"
+                }
+            }
+            div { "{saved}" }
+        }
+    })
+}
@@ -0,0 +1,160 @@
+//! Controls for the global settings
+
+use names_core::iso3166::Grouping;
+
+use crate::i18n::Locale;
+use crate::prelude::*;
+use crate::settings::*;
+
+// Component for the settings controls
+#[tracing::instrument(skip_all)]
+pub fn Settings(cx: Scope<'_>) -> Element<'_> {
+    let inline_countries = use_read(&cx, INLINE_COUNTRIES);
+    let set_inline_countries = use_set(&cx, INLINE_COUNTRIES);
+    let grouping = use_read(&cx, GROUPING).name();
+    let set_grouping = use_set(&cx, GROUPING);
+    let request_timeout = use_read(&cx, REQUEST_TIMEOUT);
+    let set_request_timeout = use_set(&cx, REQUEST_TIMEOUT);
+    let show_notes = *use_read(&cx, SHOW_NOTES);
+    let set_show_notes = use_set(&cx, SHOW_NOTES);
+    let show_variants = *use_read(&cx, SHOW_VARIANTS);
+    let set_show_variants = use_set(&cx, SHOW_VARIANTS);
+    let offline_estimates = *use_read(&cx, OFFLINE_ESTIMATES);
+    let set_offline_estimates = use_set(&cx, OFFLINE_ESTIMATES);
+    let base_url_input = use_state(&cx, || api_base_url().unwrap_or_default());
+    let locale = use_read(&cx, LOCALE);
+    let set_locale = use_set(&cx, LOCALE);
+    let msgs = locale.messages();
+
+    // Ignore anything out of range (including the transient empty string while
+    // the user is editing the number).
+    let oninput = move |ev: FormEvent| match ev.value.parse::<usize>() {
+        Ok(n) if (1..=MAX_INLINE_COUNTRIES).contains(&n) => set_inline_countries(n),
+        _ => {}
+    };
+    let oninput_timeout = move |ev: FormEvent| match ev.value.parse::<u32>() {
+        Ok(secs) if REQUEST_TIMEOUT_RANGE.contains(&secs) => set_request_timeout(secs),
+        _ => {}
+    };
+    // Only saved once the user has finished editing (so no requests go to a
+    // half-typed URL).
+    let onchange_api_base_url = move |ev: FormEvent| {
+        set_api_base_url(&ev.value);
+        base_url_input.set(ev.value.trim().to_owned());
+    };
+    let onchange_grouping = move |ev: FormEvent| {
+        if let Some(grouping) = Grouping::ALL.into_iter().find(|g| g.name() == ev.value) {
+            set_grouping(grouping);
+        }
+    };
+    let grouping_options = Grouping::ALL.iter().map(|&g| {
+        let (key, name) = (g.name(), (msgs.grouping)(g));
+        rsx! { option { key: "{key}", value: "{key}", "{name}" } }
+    });
+    let locale_options = Locale::ALL.iter().map(|l| {
+        let (tag, endonym) = (l.tag(), l.endonym());
+        rsx! { option { key: "{tag}", value: "{tag}", "{endonym}" } }
+    });
+    let onchange_locale = move |ev: FormEvent| {
+        if let Some(locale) = Locale::from_tag(&ev.value) {
+            set_locale(locale);
+        }
+    };
+
+    // Labels
+    let countries_shown_inline = msgs.countries_shown_inline;
+    let group_countries_by = msgs.group_countries_by;
+    let language = msgs.language;
+    let request_timeout_label = msgs.request_timeout;
+    let (min_timeout, max_timeout) =
+        (REQUEST_TIMEOUT_RANGE.start(), REQUEST_TIMEOUT_RANGE.end());
+    let api_base_url_label = msgs.api_base_url;
+    let api_base_url_placeholder = msgs.api_base_url_default;
+    let api_base_url_value = base_url_input.get().clone();
+    let show_notes_label = msgs.show_notes;
+    let show_variants_label = msgs.show_variants;
+    let offline_estimates_label = msgs.offline_estimates;
+    let locale_tag = locale.tag();
+
+    cx.render(rsx! {
+        div {
+            class: "settings",
+            label {
+                "{countries_shown_inline}"
+                input {
+                    class: "form-control form-control-sm",
+                    r#type: "number",
+                    min: "1",
+                    max: "{MAX_INLINE_COUNTRIES}",
+                    value: "{inline_countries}",
+                    oninput: oninput
+                }
+            }
+            label {
+                "{group_countries_by}"
+                select {
+                    class: "form-control form-control-sm",
+                    value: "{grouping}",
+                    onchange: onchange_grouping,
+                    grouping_options
+                }
+            }
+            label {
+                "{request_timeout_label}"
+                input {
+                    class: "form-control form-control-sm",
+                    r#type: "number",
+                    min: "{min_timeout}",
+                    max: "{max_timeout}",
+                    value: "{request_timeout}",
+                    oninput: oninput_timeout
+                }
+            }
+            label {
+                "{api_base_url_label}"
+                input {
+                    class: "form-control form-control-sm api-base-url",
+                    placeholder: "{api_base_url_placeholder}",
+                    value: "{api_base_url_value}",
+                    onchange: onchange_api_base_url
+                }
+            }
+            label {
+                input {
+                    class: "form-check-input",
+                    r#type: "checkbox",
+                    checked: "{show_notes}",
+                    onchange: move |_| set_show_notes(!show_notes)
+                }
+                "{show_notes_label}"
+            }
+            label {
+                input {
+                    class: "form-check-input",
+                    r#type: "checkbox",
+                    checked: "{show_variants}",
+                    onchange: move |_| set_show_variants(!show_variants)
+                }
+                "{show_variants_label}"
+            }
+            label {
+                input {
+                    class: "form-check-input",
+                    r#type: "checkbox",
+                    checked: "{offline_estimates}",
+                    onchange: move |_| set_offline_estimates(!offline_estimates)
+                }
+                "{offline_estimates_label}"
+            }
+            label {
+                "{language}"
+                select {
+                    class: "form-control form-control-sm",
+                    value: "{locale_tag}",
+                    onchange: onchange_locale,
+                    locale_options
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,83 @@
+//! Summary of the country results across every name entered
+
+use std::cmp::Ordering;
+
+use names_core::iso3166::{buckets, remainder, Bucket};
+
+use super::countries::bucket_view;
+use crate::nicknames;
+use crate::overrides::Overrides;
+use crate::prelude::*;
+use crate::settings::{GROUPING, INLINE_COUNTRIES, LOCALE};
+
+// Component to display the aggregate country results, grouped as per the
+// current setting, averaged over all names with results so far. For common
+// names, this is often more meaningful than any single name's countries.
+#[tracing::instrument(skip_all)]
+#[inline_props]
+pub fn Summary(
+    cx: Scope<'_>,
+    names: UseState<Names>,
+    db: UseRef<Db>,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let inline = *use_read(&cx, INLINE_COUNTRIES);
+    let grouping = *use_read(&cx, GROUPING);
+    let msgs = use_read(&cx, LOCALE).messages();
+
+    // Gather the country results so far (for the full forms of nicknames,
+    // where those are shown in the table), giving each name equal weight. As
+    // in the table, any manual override counts as a single certain country.
+    let db = db.read();
+    let overrides = overrides.read();
+    let results: Vec<_> = names
+        .iter()
+        .filter_map(|name| {
+            if let Some(country) = overrides.get(name).and_then(|o| o.country.clone()) {
+                return Some(vec![CountryResult { country, probability: 1.0 }]);
+            }
+            match &nicknames::results(&db, &overrides, name).0?.country {
+                Remote::Success(countries) => Some(countries.clone()),
+                _ => None,
+            }
+        })
+        .collect();
+    if results.is_empty() {
+        return None;
+    }
+    let weight = 1.0 / results.len() as f32;
+
+    // Average the per-name buckets.
+    let mut totals: Vec<Bucket> = Vec::new();
+    for bucket in results.iter().flat_map(|countries| buckets(countries, grouping, msgs))
+    {
+        let probability = bucket.probability * weight;
+        match totals.iter_mut().find(|b| b.label == bucket.label) {
+            Some(total) => total.probability += probability,
+            None => totals.push(Bucket { probability, ..bucket }),
+        }
+    }
+    totals.sort_by(|a, b| {
+        b.probability.partial_cmp(&a.probability).unwrap_or(Ordering::Equal)
+    });
+    totals.truncate(inline);
+    let other = remainder(&totals);
+    let heading = (msgs.across_all_names)(results.len());
+    let other_label = msgs.other;
+
+    cx.render(rsx! {
+        div {
+            class: "summary",
+            h5 { "{heading}" }
+            div {
+                class: "countries",
+                totals.iter().map(bucket_view)
+                div {
+                    class: "country other",
+                    ConfidenceBar { probability: other }
+                    "{other_label}"
+                }
+            }
+        }
+    })
+}
@@ -1,122 +1,212 @@
 //! Render the results table
+//!
+//! This needs to cope with pasting in an entire HR export, so is windowed: only
+//! the rows actually on screen are mounted, and each row is memoised. The
+//! target is smooth scrolling with 50,000 names. The rows on screen are also
+//! passed on to the request [Queue], so their names are looked up first.
 
+use std::ops::Range;
+
+use gloo::events::EventListener;
+
+use crate::db::Queue;
+use crate::i18n::Messages;
+use crate::nicknames;
+use crate::overrides::{Override, Overrides};
 use crate::prelude::*;
+use crate::settings::SHOW_NOTES;
 
-/// Percent probability threshold for an unconditional M or F
-const CERTAIN_THRESHOLD: f32 = 85.0;
+/// Element id of the table
+const TABLE_ID: &str = "results";
 
-/// Percent probability threshold for a probable M or F
-const PROBABLE_THRESHOLD: f32 = 75.0;
+/// Height of each table row, in pixels, which must match `#results tr` in
+/// css/main.scss. Rows need a fixed height for the windowing arithmetic.
+const ROW_HEIGHT: f64 = 33.0;
+
+/// Number of extra rows to mount above and below those on screen, so that
+/// scrolling doesn't flash up empty space before the next render.
+const OVERSCAN: usize = 10;
 
 // Component to display results table
 #[tracing::instrument(skip_all)]
 #[inline_props]
-pub fn Table(cx: Scope<'_>, names: UseState<Names>, db: UseRef<Db>) -> Element<'_> {
-    let table_row = |i, name| {
-        let db = db.read();
-        rsx! {
-            tr {
-                key: "{i}-{name}",
-                td { "{name}" }
-
-                // Gender
-                db.get(name).unwrap().gender.render(|r| {
-                    let label = r.summarised();
-                    rsx! {
-                        td {
-                            ConfidenceBar { probability: r.probability }
-                            "{label}"
-                        }
-                    }
-                })
+pub fn Table(
+    cx: Scope<'_>,
+    names: UseState<Names>,
+    db: UseRef<Db>,
+    queue: UseRef<Queue>,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let show_notes = *use_read(&cx, SHOW_NOTES);
 
-                // Countries
-                db.get(name).unwrap().country.render(|r| {
-                    rsx! {
-                        td {
-                            div {
-                                class: "countries",
-                                r.iter().enumerate().map(|(n, c)| {
-                                    let country = crate::iso3166::lookup(&c.country).unwrap_or(&c.country);
-                                    rsx! {
-                                        div {
-                                            key: "{n}",
-                                            class: "country",
-                                            ConfidenceBar { probability: c.probability }
-                                            "{country}"
-                                        }
-                                    }
-                                })
-                            }
-                        }
+    // Track which rows are on screen, updating on any scroll or resize. The
+    // listeners live in the hook, so are removed along with the component.
+    let visible = use_state(&cx, visible_rows);
+    cx.use_hook(|_| {
+        window().map(|window| {
+            ["scroll", "resize"].map(|event| {
+                to_owned![visible];
+                EventListener::new(&window, event, move |_| {
+                    let rows = visible_rows();
+                    if *visible.current() != rows {
+                        visible.set(rows);
                     }
                 })
+            })
+        })
+    });
+
+    // Only mount the visible rows, with spacers standing in for the rest.
+    let end = visible.end.min(names.len());
+    let start = visible.start.min(end);
+    let top_spacer = start as f64 * ROW_HEIGHT;
+    let bottom_spacer = (names.len() - end) as f64 * ROW_HEIGHT;
+
+    // Have the names on screen (and the full forms of any nicknames among them)
+    // fetched first. (This is during rendering, so mustn't itself trigger a
+    // re-render.)
+    let overrides_map = overrides.read();
+    let on_screen = &names[start..end];
+    let full_forms = on_screen
+        .iter()
+        .filter_map(|name| nicknames::full_form_for(name, &overrides_map))
+        .map(Name::from_static);
+    let visible: Names = on_screen.iter().cloned().chain(full_forms).collect();
+    queue.write_silent().set_visible(&visible);
+
+    let db = db.read();
+    let rows = on_screen.iter().enumerate().map(|(i, name)| {
+        let i = start + i;
+        let manual = overrides_map.get(name).cloned().unwrap_or_default();
+        let (results, via_full_form) = nicknames::results(&db, &overrides_map, name);
+        rsx! {
+            Row {
+                key: "{i}-{name}",
+                name: name.clone(),
+                results: results.cloned().unwrap_or_default(),
+                via_full_form: via_full_form.is_some(),
+                manual: manual,
+                show_notes: show_notes,
+                overrides: overrides.clone()
             }
         }
-    };
+    });
+
     cx.render(rsx! {
         table {
+            id: "{TABLE_ID}",
             class: "table table-sm table-bordered",
-            names.iter().enumerate().map(|(i, name)| {
-                table_row(i, name)
-            })
+            tr { class: "spacer", style: "height: {top_spacer}px" }
+            rows
+            tr { class: "spacer", style: "height: {bottom_spacer}px" }
         }
     })
 }
 
-// Render a confidence bar and label, for a given probability
+// One row of the table, with the results for the name itself, or for its full
+// form if it's a nickname and those were preferred. All the props are owned
+// values, so dioxus memoises this, and only re-renders the row when its name,
+// results or overrides change.
 #[inline_props]
-fn ConfidenceBar(cx: Scope<'_>, probability: f32) -> Element<'_> {
-    let prob = f32::round(probability * 100.0) as u8;
-    let prob_str = if prob > 0 { format!("{prob}%") } else { String::from("") };
+fn Row(
+    cx: Scope<'_>,
+    name: Name,
+    results: AllResults,
+    via_full_form: bool,
+    manual: Override,
+    show_notes: bool,
+    overrides: UseRef<Overrides>,
+) -> Element<'_> {
+    let notes = show_notes.then(|| {
+        rsx! {
+            NoteCell {
+                name: name.clone(),
+                note: manual.note.clone(),
+                overrides: overrides.clone()
+            }
+        }
+    });
     cx.render(rsx! {
-        div {
-            class: "confidence",
-            style: "width: {prob}%",
-            "{prob_str}"
+        tr {
+            NameCell {
+                name: name.clone(),
+                via_full_form: *via_full_form,
+                skip_full_form: manual.skip_full_form,
+                overrides: overrides.clone()
+            }
+            GenderCell {
+                name: name.clone(),
+                gender: results.gender.clone(),
+                manual: manual.clone(),
+                overrides: overrides.clone()
+            }
+            CountryCell {
+                name: name.clone(),
+                country: results.country.clone(),
+                manual: manual.clone(),
+                overrides: overrides.clone()
+            }
+            notes
         }
     })
 }
 
-impl crate::api::GenderResult {
-    /// Plain-text representation of a gender result.
-    pub fn summarised(&self) -> &'static str {
-        let prob = f32::round(self.probability * 100.0);
-        match (self.gender, prob >= CERTAIN_THRESHOLD, prob >= PROBABLE_THRESHOLD) {
-            (Some(Gender::Female), true, _) => "F",
-            (Some(Gender::Female), _, true) => "F?",
-            (Some(Gender::Female), _, _) => "F??",
-            (Some(Gender::Male), true, _) => "M",
-            (Some(Gender::Male), _, true) => "M?",
-            (Some(Gender::Male), _, _) => "M??",
-            _ => "?",
-        }
-    }
+/// Work out which rows are on screen (plus some overscan), from where the table
+/// currently sits in the browser viewport. Before the table is mounted, this
+/// assumes it starts at the top (and without a browser, that there's no room
+/// for any rows but the overscan).
+fn visible_rows() -> Range<usize> {
+    let window = window();
+    let viewport = window
+        .as_ref()
+        .and_then(|window| window.inner_height().ok())
+        .and_then(|h| h.as_f64())
+        .unwrap_or_default();
+    let top = window
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(TABLE_ID))
+        .map_or(0.0, |table| table.get_bounding_client_rect().top());
+    let first = (-top / ROW_HEIGHT).floor().max(0.0) as usize;
+    let last = ((viewport - top) / ROW_HEIGHT).ceil().max(0.0) as usize;
+    first.saturating_sub(OVERSCAN)..last + OVERSCAN
+}
+
+/// Rendering of [Remote] results in table cells
+pub(super) trait RenderRemote<T> {
+    /// Render the contents of a table cell for a [Remote<T>], given a closure
+    /// for the success case
+    fn render<'a>(
+        &self,
+        msgs: &'static Messages,
+        render: impl Fn(&T) -> LazyNodes<'a, '_>,
+    ) -> LazyNodes<'a, '_>;
 }
 
-impl<T> Remote<T> {
-    /// Render a [Remote<T>], given a closure for the success case
-    fn render<'a>(&self, render: impl Fn(&T) -> LazyNodes<'a, '_>) -> LazyNodes<'a, '_> {
+impl<T> RenderRemote<T> for Remote<T> {
+    fn render<'a>(
+        &self,
+        msgs: &'static Messages,
+        render: impl Fn(&T) -> LazyNodes<'a, '_>,
+    ) -> LazyNodes<'a, '_> {
         match self {
             Remote::Loading => rsx! {
-                td {
+                div {
+                    class: "progress",
                     div {
-                        class: "progress",
-                        div {
-                            class: "progress-bar progress-bar-striped progress-bar-animated",
-                            style: "width: 100%;"
-                        }
+                        class: "progress-bar progress-bar-striped progress-bar-animated",
+                        style: "width: 100%;"
                     }
                 }
             },
-            Remote::Error => rsx! {
-                td {
+            Remote::Error => {
+                let label = msgs.error_badge;
+                rsx! {
                     span {
                         class: "badge badge-pill badge-danger px-5 py-1",
-                        "⚠ Error"
+                        "{label}"
                     }
                 }
-            },
+            }
             Remote::Success(r) => render(r),
         }
     }
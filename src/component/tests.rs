@@ -0,0 +1,375 @@
+//! Snapshot tests of the components, rendered with dioxus SSR against prepared
+//! app state. These run natively with `cargo test`, as well as in a headless
+//! browser (`wasm-pack test --headless --firefox`).
+//!
+//! Each snapshot in `snapshots/` holds the rendered HTML laid out one tag or
+//! text per line, so that a failing test shows exactly what changed. If the
+//! change is intended, replace the snapshot with the output from the failure.
+
+use dioxus::ssr::render_vdom;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use super::table::RenderRemote;
+use crate::db::Queue;
+use crate::i18n::Locale;
+use crate::overrides::Overrides;
+use crate::prelude::*;
+use crate::settings::LOCALE;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Elements which never have any content, so need no closing tag
+const VOID_ELEMENTS: [&str; 6] = ["br", "hr", "img", "input", "link", "meta"];
+
+/// What to render in the [Harness]
+#[derive(Debug, Clone, PartialEq)]
+enum View {
+    Table,
+    Errors,
+    CopyButton,
+    ConfidenceBar(f32),
+    /// A table row with one cell per result, rendered via [RenderRemote]
+    Remote(Vec<Remote<GenderResult>>),
+    /// The summary, with manual country overrides for some names
+    Summary(Vec<(&'static str, &'static str)>),
+}
+
+#[derive(Props, PartialEq)]
+struct HarnessProps {
+    names: Names,
+    db: Db,
+    view: View,
+}
+
+// Test harness: set up the app state that the component under test needs, and
+// render it, in English whatever the browser's language.
+fn Harness(cx: Scope<'_, HarnessProps>) -> Element<'_> {
+    let set_locale = use_set(&cx, LOCALE);
+    cx.use_hook(|_| set_locale(Locale::En));
+    let names = use_state(&cx, || cx.props.names.clone());
+    let db = use_ref(&cx, || cx.props.db.clone());
+    let queue = use_ref(&cx, Queue::default);
+    let overrides = use_ref(&cx, || {
+        let mut overrides = Overrides::default();
+        if let View::Summary(countries) = &cx.props.view {
+            for &(name, country) in countries {
+                let country = Some(country.to_owned());
+                overrides.edit(&Name::from_ref(name), |o| o.country = country);
+            }
+        }
+        overrides
+    });
+
+    match &cx.props.view {
+        View::Table => cx.render(rsx! {
+            Table {
+                names: names.clone(),
+                db: db.clone(),
+                queue: queue.clone(),
+                overrides: overrides.clone()
+            }
+        }),
+        View::Errors => cx.render(rsx! {
+            Errors { db: db.clone(), queue: queue.clone() }
+        }),
+        View::CopyButton => cx.render(rsx! {
+            CopyButton { names: names.clone(), db: db.clone(), overrides: overrides.clone() }
+        }),
+        View::Summary(_) => cx.render(rsx! {
+            Summary { names: names.clone(), db: db.clone(), overrides: overrides.clone() }
+        }),
+        View::ConfidenceBar(probability) => cx.render(rsx! {
+            ConfidenceBar { probability: *probability }
+        }),
+        View::Remote(remotes) => {
+            let msgs = Locale::En.messages();
+            let cells = remotes.iter().map(|remote| {
+                let contents = remote.render(msgs, |r| {
+                    let label = r.summarised();
+                    rsx! { "{label}" }
+                });
+                rsx! { td { contents } }
+            });
+            cx.render(rsx! { tr { cells } })
+        }
+    }
+}
+
+/// Internal helper function: a virtual DOM for a view of the given state,
+/// after its first render.
+fn build(names: &[&str], db: Db, view: View) -> VirtualDom {
+    let names = names.iter().map(|&name| Name::from_ref(name)).collect();
+    let mut dom = VirtualDom::new_with_props(Harness, HarnessProps { names, db, view });
+    _ = dom.rebuild();
+    dom
+}
+
+/// Internal helper function: render a view of the given state.
+fn render(names: &[&str], db: Db, view: View) -> String {
+    render_vdom(&build(names, db, view))
+}
+
+/// Internal helper function: lay out HTML one tag or text per line, indented
+/// by depth. Comments (which dioxus uses as placeholders) are dropped, and void
+/// elements normalised, so the snapshots are of the markup itself.
+fn layout(html: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut rest = html;
+    while !rest.is_empty() {
+        let end = match rest.starts_with('<') {
+            true => rest.find('>').map_or(rest.len(), |i| i + 1),
+            false => rest.find('<').unwrap_or(rest.len()),
+        };
+        let (token, tail) = rest.split_at(end);
+        rest = tail;
+        let token = token.trim();
+        let tag = token.trim_start_matches(&['<', '/'][..]);
+        let tag = &tag[..tag.find(&[' ', '>', '/'][..]).unwrap_or(tag.len())];
+        let void = token.starts_with('<') && VOID_ELEMENTS.contains(&tag);
+        if token.is_empty()
+            || token.starts_with("<!--")
+            || (void && token.starts_with("</"))
+        {
+            continue;
+        }
+        if token.starts_with("</") {
+            depth -= 1;
+        }
+        out.push_str(&"  ".repeat(depth));
+        match token.strip_suffix("/>") {
+            Some(open) if void => {
+                out.push_str(open.trim_end());
+                out.push('>');
+            }
+            _ => out.push_str(token),
+        }
+        out.push('\n');
+        if token.starts_with('<') && !token.starts_with("</") && !void {
+            depth += 1;
+        }
+    }
+    out
+}
+
+/// Internal helper function: check rendered HTML against a snapshot.
+fn check_snapshot(name: &str, snapshot: &str, html: &str) {
+    let actual = layout(html);
+    assert!(
+        actual.trim() == snapshot.trim(),
+        "Snapshot {name} doesn't match. If the change is intended, replace \
+         src/component/snapshots/{name}.html with:\n{actual}"
+    );
+}
+
+/// Check rendered HTML against a snapshot in `snapshots/`.
+macro_rules! assert_snapshot {
+    ($name:literal, $html:expr) => {
+        check_snapshot($name, include_str!(concat!("snapshots/", $name, ".html")), &$html)
+    };
+}
+
+/// Internal helper function: a gender result.
+fn gender(gender: Option<Gender>, probability: f32) -> Remote<GenderResult> {
+    Remote::Success(GenderResult { gender, probability, count: 1000, offline: false })
+}
+
+/// Internal helper function: a country result.
+fn countries(countries: &[(&str, f32)]) -> Remote<Vec<CountryResult>> {
+    Remote::Success(
+        countries
+            .iter()
+            .map(|&(country, probability)| CountryResult {
+                country: country.to_owned(),
+                probability,
+            })
+            .collect(),
+    )
+}
+
+/// Internal helper function: a [Db] holding the given results.
+fn db(
+    results: impl IntoIterator<
+        Item = (&'static str, Remote<GenderResult>, Remote<Vec<CountryResult>>),
+    >,
+) -> Db {
+    results
+        .into_iter()
+        .map(|(name, gender, country)| {
+            (Name::from_static(name), AllResults { gender, country })
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn table_loading() {
+    // Names not in the [Db] yet, as well as those in flight
+    let db = db([("Anna", Remote::Loading, Remote::Loading)]);
+    assert_snapshot!("table_loading", render(&["Anna", "Peter"], db, View::Table));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn table_error() {
+    let db = db([("Anna", Remote::Error, Remote::Error)]);
+    assert_snapshot!("table_error", render(&["Anna"], db, View::Table));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn table_success() {
+    let db = db([
+        ("Anna", gender(Some(Gender::Female), 0.98), countries(&[("FR", 0.8)])),
+        ("Xyzzy", gender(None, 0.0), countries(&[])),
+    ]);
+    assert_snapshot!("table_success", render(&["Anna", "Xyzzy"], db, View::Table));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn table_mixed() {
+    let db = db([
+        ("Anna", gender(Some(Gender::Female), 0.98), Remote::Loading),
+        ("Jean", gender(Some(Gender::Male), 0.8), Remote::Error),
+        ("Kim", Remote::Error, countries(&[("FR", 1.0)])),
+    ]);
+    let names = ["Anna", "Jean", "Kim", "Peter"];
+    assert_snapshot!("table_mixed", render(&names, db, View::Table));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn table_empty() {
+    assert_snapshot!("table_empty", render(&[], Db::default(), View::Table));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn summary_overridden() {
+    // Jean's override replaces the API's country, and Kim's stands in for
+    // results not back yet.
+    let db = db([
+        ("Anna", Remote::Loading, countries(&[("FR", 1.0)])),
+        ("Jean", Remote::Loading, countries(&[("DE", 1.0)])),
+        ("Kim", Remote::Loading, Remote::Loading),
+    ]);
+    let overrides = vec![("Jean", "GB"), ("Kim", "GB")];
+    assert_snapshot!(
+        "summary_overridden",
+        render(&["Anna", "Jean", "Kim"], db, View::Summary(overrides))
+    );
+}
+
+// This is the only test to render [Errors], since its static handle to the
+// event loop is only ever set once.
+#[wasm_bindgen_test]
+async fn errors() {
+    let mut dom = build(&[], Db::default(), View::Errors);
+    let names = |names: &[&str]| names.iter().map(|&name| Name::from_ref(name)).collect();
+    let server_error = ApiError::ServerError(503, "Service Unavailable".to_owned());
+    add_error(AppError::api(&server_error, Service::Gender, names(&["Anna", "Peter"])));
+    add_error(AppError::api(&server_error, Service::Gender, names(&["Peter", "Jean"])));
+    let invalid_key = ApiError::InvalidApiKey("Invalid API key".to_owned());
+    let many: Vec<_> = (0..22).map(|i| format!("Name{i}")).collect();
+    let many: Vec<_> = many.iter().map(String::as_str).collect();
+    add_error(AppError::api(&invalid_key, Service::Country, names(&many)));
+    add_error(AppError::notice(
+        Notice::Cancelled,
+        Some(Service::Gender),
+        names(&["Kim"]),
+    ));
+
+    // Let the event loop take them in, and render the result.
+    dom.wait_for_work().await;
+    _ = dom.work_with_deadline(|| false);
+    assert_snapshot!("errors", render_vdom(&dom));
+}
+
+// libtest can't run async tests itself.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn errors_native() { futures::executor::block_on(errors()) }
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn copy_button() {
+    assert_snapshot!("copy_button", render(&["Anna"], Db::default(), View::CopyButton));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn copy_button_disabled() {
+    // Nothing to copy
+    assert_snapshot!(
+        "copy_button_disabled",
+        render(&[], Db::default(), View::CopyButton)
+    );
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn remote_render() {
+    let remotes = vec![
+        Remote::Loading,
+        Remote::Error,
+        gender(Some(Gender::Female), 0.98),
+        gender(Some(Gender::Male), 0.8),
+        gender(None, 0.0),
+    ];
+    assert_snapshot!("remote_render", render(&[], Db::default(), View::Remote(remotes)));
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn confidence_bar() {
+    // Rounded to the nearest percent, with no label for 0%, and anything out of
+    // range clamped
+    for (probability, width, label) in [
+        (0.0, 0, ""),
+        (0.004, 0, ""),
+        (0.006, 1, "1%"),
+        (0.5, 50, "50%"),
+        (0.994, 99, "99%"),
+        (0.996, 100, "100%"),
+        (1.0, 100, "100%"),
+        (1.2, 100, "100%"),
+        (-0.1, 0, ""),
+    ] {
+        assert_eq!(
+            render(&[], Db::default(), View::ConfidenceBar(probability)),
+            format!(r#"<div class="confidence" style="width: {width}%">{label}</div>"#),
+            "probability {probability}"
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn snapshot_layout() {
+    assert_eq!(
+        layout(
+            r#"<div class="a"><!--placeholder--><input value="x"/>text <b>bold</b></div>"#
+        ),
+        "<div class=\"a\">\n  <input value=\"x\">\n  text\n  <b>\n    bold\n  \
+         </b>\n</div>\n"
+    );
+}
+
+#[wasm_bindgen_test]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn api_errors_localised() {
+    // Shown in the UI language, rather than the English of their Display
+    let errors = [
+        ApiError::Timeout,
+        ApiError::LimitExceeded,
+        ApiError::ServerError(503, "Service Unavailable".to_owned()),
+    ];
+    for err in errors {
+        let english = (Locale::En.messages().api_error)(&err);
+        assert_eq!(english, err.to_string());
+        for locale in [Locale::Fr, Locale::De] {
+            assert_ne!((locale.messages().api_error)(&err), english, "{locale:?}");
+        }
+    }
+}
@@ -1,98 +1,326 @@
-//! In-memory "database" that manages and caches API results
+//! Drives the request [Queue] from [names_core::queue] with the browser's
+//! `fetch`, keeping the results in the [Db] from [names_core::db].
 
 use std::rc::Rc;
 
-use futures::future::LocalBoxFuture;
+use futures::future::{try_join_all, LocalBoxFuture};
+use gloo::events::EventListener;
+use names_core::db::{self as cache, Selector};
+use names_core::queue::{Batch, Outcome};
+use web_sys::{AbortController, AbortSignal};
 
+use crate::offline;
 use crate::prelude::*;
 
-/// Maximum number of queries per batched API call
-const API_CHUNKS: usize = 10;
+/// Book-keeping for all API requests, queued or in progress, including the
+/// lookups for the page for a single name. Each in-flight batch can be aborted
+/// via its [AbortController].
+pub type Queue = names_core::queue::Queue<AbortController, DetailLookup>;
 
-/// See if any [Names] have not yet been presented to the API, and if so,
-/// kickoff those API requests. When the results or errors come back, handle
+/// See if any [Names] have not yet been presented to the API, and if so, queue
+/// them up for the schedulers. When the results or errors come back, handle
 /// those appropriately.
-pub fn start_any_requests(names: &Names, db: UseRef<Db>) {
-    let mut names_to_query = Vec::new();
-    for name in names {
-        // Technically, we should arguably also do a new fetch if there was
-        // a previous attempt, but it ended in Remote::Error. But the odds
-        // of that happening, and being useful to re-try now, are so low
-        // that we just keep things simple, and the user can refresh the
-        // page if they want to force re-fetches.
-        if db.read().get(name).is_none() {
-            db.write().insert(name.to_owned(), AllResults::default());
-            names_to_query.push(name.to_owned());
+pub fn start_any_requests(names: Rc<Names>, db: UseRef<Db>, queue: UseRef<Queue>) {
+    // This is called during rendering, so updating the queue mustn't itself
+    // trigger a re-render.
+    {
+        let mut queue = queue.write_silent();
+        queue.set_wanted(names.clone(), AbortController::abort);
+        // Technically, we should arguably also do a new fetch if there was a
+        // previous attempt, but it ended in Remote::Error. But the odds of that
+        // happening, and being useful to re-try now, are so low that we just
+        // keep things simple, and the user can refresh the page if they want
+        // to force re-fetches.
+        let queued = cache::insert_new(&mut db.write(), &names);
+        for name in queued.iter() {
+            for service in Service::ALL {
+                queue.enqueue(service, name.to_owned());
+            }
+        }
+        if !queued.is_empty() {
+            queue.prioritise();
+            if queue.offline() {
+                offline::estimate(&db, queued);
+            }
+        }
+    }
+    pump(&db, &queue, true);
+}
+
+/// Pause or resume sending requests. Anything already in flight carries on
+/// regardless.
+pub fn set_paused(db: &UseRef<Db>, queue: &UseRef<Queue>, paused: bool) {
+    queue.write().set_paused(paused);
+    if paused {
+        add_error(AppError::notice(Notice::Paused, None, vec![]));
+    } else {
+        pump(db, queue, false);
+    }
+}
+
+/// Pause sending requests whenever the browser goes offline (making do with
+/// offline estimates meanwhile), and resume when it comes back online. Returns
+/// the event listeners, which stop watching when dropped.
+pub fn watch_connectivity(db: &UseRef<Db>, queue: &UseRef<Queue>) -> [EventListener; 2] {
+    queue.write_silent().set_offline(!is_online());
+    let window = web_sys::window().unwrap();
+    [("online", false), ("offline", true)].map(|(event, offline)| {
+        to_owned![db, queue];
+        EventListener::new(&window, event, move |_| {
+            queue.write().set_offline(offline);
+            if offline {
+                add_error(AppError::notice(Notice::Offline, None, vec![]));
+                offline::estimate(&db, db.read().keys().cloned().collect());
+            } else {
+                pump(&db, &queue, false);
+            }
+        })
+    })
+}
+
+/// Queue lookups for the page for a single name. This is called during
+/// rendering, so silently.
+pub fn look_up_detail(
+    db: &UseRef<Db>,
+    queue: &UseRef<Queue>,
+    lookups: impl IntoIterator<Item = DetailLookup>,
+) {
+    {
+        let mut queue = queue.write_silent();
+        for lookup in lookups {
+            queue.enqueue_detail(lookup);
+        }
+    }
+    pump(db, queue, true);
+}
+
+/// Whether the browser thinks it is online
+fn is_online() -> bool { web_sys::window().unwrap().navigator().on_line() }
+
+/// Look the given names up again for one service, after their lookups failed
+/// (or were only estimated offline).
+pub fn retry(db: &UseRef<Db>, queue: &UseRef<Queue>, service: Service, names: &[Name]) {
+    {
+        let mut queue = queue.write();
+        for name in cache::reset_failed(&mut db.write(), service, names) {
+            queue.enqueue(service, name);
         }
+        queue.prioritise();
     }
+    pump(db, queue, false);
+}
 
-    // If we have no uncached names, nothing to do.
-    if names_to_query.is_empty() {
-        return;
+/// Send as many requests as the schedulers currently allow. Silently, if called
+/// during rendering.
+fn pump(db: &UseRef<Db>, queue: &UseRef<Queue>, silent: bool) {
+    let (dropped, batches, details) = {
+        let mut queue = if silent { queue.write_silent() } else { queue.write() };
+        let dropped = queue.prune();
+        let mut batches = Vec::new();
+        for service in Service::ALL {
+            while let Some(batch) =
+                queue.start_batch(service, now(), || AbortController::new().unwrap())
+            {
+                batches.push((service, batch));
+            }
+        }
+        let mut details = Vec::new();
+        while let Some(batch) =
+            queue.start_detail(now(), || AbortController::new().unwrap())
+        {
+            details.push(batch);
+        }
+        (dropped, batches, details)
+    };
+    forget_unwanted(db, queue, &dropped);
+
+    for batch in details {
+        spawn_detail_request(batch, db.clone(), queue.clone());
     }
 
-    // Move vec into an Rc, so it can be shared across the two parallel async
-    // API queries.
-    let names_to_query = Rc::new(names_to_query);
-
-    // In parallel, kick off the Gender API request...
-    spawn_api_request(
-        names_to_query.clone(),
-        db.clone(),
-        |names| Box::pin(get_genders(names)),
-        |api_value| &mut api_value.gender,
-    );
-
-    // ...and the Country API request.
-    spawn_api_request(
-        names_to_query,
-        db,
-        |names| Box::pin(get_countries(names)),
-        |api_value| &mut api_value.country,
-    );
+    for (service, batch) in batches {
+        let (db, queue) = (db.clone(), queue.clone());
+        match service {
+            Service::Gender => spawn_api_request(
+                service,
+                batch,
+                db,
+                queue,
+                |names, signal, timeout| {
+                    Box::pin(async move {
+                        let client = WebClient { signal: Some(&signal), timeout };
+                        get_genders(&client, &api_config(), names).await
+                    })
+                },
+                |api_value| &mut api_value.gender,
+            ),
+            Service::Country => spawn_api_request(
+                service,
+                batch,
+                db,
+                queue,
+                |names, signal, timeout| {
+                    Box::pin(async move {
+                        let client = WebClient { signal: Some(&signal), timeout };
+                        get_countries(&client, &api_config(), names).await
+                    })
+                },
+                |api_value| &mut api_value.country,
+            ),
+        }
+    }
 }
 
 /// Common handling: fire off an API request in a fresh async task, and deal
 /// with either the success or failure result.
 fn spawn_api_request<T: Clone + 'static>(
-    names_to_query: Rc<Names>,
+    service: Service,
+    batch: Batch<AbortController>,
     db: UseRef<Db>,
-    fetch: impl Fn(&[Name]) -> LocalBoxFuture<'_, ApiResult<T>> + 'static,
-    selector: fn(&mut AllResults) -> &mut Remote<T>,
+    queue: UseRef<Queue>,
+    fetch: impl Fn(&[Name], AbortSignal, u32) -> LocalBoxFuture<'_, ApiResult<T>> + 'static,
+    selector: Selector<T>,
 ) {
     spawn_local(async move {
-        // Fire off the API request - can do up to 10 names per request.
-        // Keep track of the indices into the vector for each query, so
-        // we can associate errors with names.
-        for chunked_names in names_to_query.chunks(API_CHUNKS) {
-            // Remember these particular names, so we can associate an error
-            // with them. (We could just save the starting/ending indices into
-            // the existing Rc<Vec> but yolo.)
-            let saved_names = chunked_names.to_vec();
-
-            // Issue the "fetch" API request and parse the JSON.
-            let result = fetch(chunked_names).await;
-            let mut db = db.write();
-            match result {
-                // Success! Update the per-name signals with the result.
-                Ok(result) => {
-                    for (name, item) in result.into_iter() {
-                        *selector(db.get_mut(&name).unwrap()) = Remote::Success(item);
-                    }
+        // Issue the "fetch" API request and parse the JSON
+        let result = fetch(&batch.names, batch.handle.signal(), batch.timeout).await;
+
+        // Aborted, since none of the names are wanted any more: let the user
+        // know, and forget the names.
+        if batch.handle.signal().aborted() {
+            add_error(AppError::notice(
+                Notice::Cancelled,
+                Some(service),
+                batch.names.clone(),
+            ));
+            queue.write().cancelled(service, batch.id, now());
+            forget_unwanted(&db, &queue, &batch.names);
+            pump(&db, &queue, false);
+            return;
+        }
+
+        // Stop waiting for any response that has timed out.
+        if matches!(result, Err(ApiError::Timeout)) {
+            batch.handle.abort();
+        }
+
+        let outcome = queue.write().finished(
+            service,
+            batch,
+            result.as_ref().err(),
+            now(),
+            is_online(),
+        );
+        match (outcome, result) {
+            // Success! Update the per-name signals with the result.
+            (Outcome::Done, Ok(result)) => {
+                cache::store(&mut db.write(), result, selector)
+            }
+
+            // Too many requests: wait a while, then try again.
+            (Outcome::BackOff(delay), _) => {
+                wait_out(delay, db.clone(), queue.clone(), move |queue| {
+                    queue.end_backoff(service);
+                });
+            }
+
+            // Failure: publish the error, and set the per-name entry for every
+            // failed name to the error state. If it's the API that's the
+            // problem, rather than the names, estimate their genders offline.
+            (Outcome::Failed(failed), Err(err)) => {
+                add_error(AppError::api(&err, service, failed.clone()));
+                cache::fail(&mut db.write(), &failed, selector);
+                let unavailable = matches!(
+                    err,
+                    ApiError::Http(_)
+                        | ApiError::Timeout
+                        | ApiError::LimitExceeded
+                        | ApiError::ServerError(..)
+                );
+                if service == Service::Gender && unavailable {
+                    offline::estimate(&db, failed);
                 }
+            }
+
+            // Nothing to do until the names come round again.
+            _ => {}
+        }
+
+        pump(&db, &queue, false);
+    });
+}
+
+/// Fire off a lookup for the page for a single name in a fresh async task, and
+/// deal with the outcome much as [spawn_api_request] does. The lookup itself
+/// stores any result.
+fn spawn_detail_request(
+    batch: Batch<AbortController, DetailLookup>,
+    db: UseRef<Db>,
+    queue: UseRef<Queue>,
+) {
+    spawn_local(async move {
+        let signal = batch.handle.signal();
+        let client = WebClient { signal: Some(&signal), timeout: batch.timeout };
+        let result = try_join_all(batch.names.iter().map(|lookup| lookup.fetch(&client)))
+            .await
+            .map(|_| ());
 
-                // Failure
-                Err(err) => {
-                    // First publish the actual error message...
-                    add_err_msg(err.to_string());
+        // Stop waiting for any response that has timed out.
+        if matches!(result, Err(ApiError::Timeout)) {
+            batch.handle.abort();
+        }
+
+        let outcome = queue.write().finished_detail(
+            batch,
+            result.as_ref().err(),
+            now(),
+            is_online(),
+        );
+        match (outcome, result) {
+            // Too many requests: wait a while, then try again.
+            (Outcome::BackOff(delay), _) => {
+                wait_out(delay, db.clone(), queue.clone(), Queue::end_detail_backoff);
+            }
 
-                    // ...then set the per-name entry for everything that
-                    // was waiting on this to the error state.
-                    for name in saved_names.into_iter() {
-                        db.get_mut(&name).unwrap().gender = Remote::Error;
-                    }
+            // Failure: publish the error, and show it on the page.
+            (Outcome::Failed(failed), Err(err)) => {
+                let names = failed.iter().map(|lookup| lookup.name().clone()).collect();
+                add_error(AppError::detail(&err, names));
+                for lookup in failed.iter() {
+                    lookup.failed();
                 }
             }
+
+            // Success, or nothing to do until the lookup comes round again
+            _ => {}
         }
+
+        pump(&db, &queue, false);
     });
 }
+
+/// Internal helper function: once `delay` milliseconds are up, stop backing off
+/// (via `end`), and carry on sending requests.
+fn wait_out(
+    delay: u32,
+    db: UseRef<Db>,
+    queue: UseRef<Queue>,
+    end: impl FnOnce(&mut Queue) + 'static,
+) {
+    spawn_local(async move {
+        TimeoutFuture::new(delay).await;
+        end(&mut queue.write());
+        pump(&db, &queue, false);
+    });
+}
+
+/// Remove cache entries for names that are (still) no longer wanted, so if they
+/// are entered again, they get queried afresh.
+fn forget_unwanted(db: &UseRef<Db>, queue: &UseRef<Queue>, names: &[Name]) {
+    if !names.is_empty() {
+        cache::forget_unwanted(&mut db.write(), &queue.read(), names);
+    }
+}
+
+/// Current time, in milliseconds
+fn now() -> f64 { web_sys::window().unwrap().performance().unwrap().now() }
@@ -0,0 +1,163 @@
+//! Errors for display to the user: what went wrong, how much it matters, and
+//! what they can do about it.
+
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+/// How serious an error is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Not a problem as such, just something worth knowing
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    /// Bootstrap alert class
+    pub fn alert_class(self) -> &'static str {
+        match self {
+            Severity::Info => "alert-info",
+            Severity::Warn => "alert-warning",
+            Severity::Error => "alert-danger",
+        }
+    }
+}
+
+/// What an error is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// An API request failed
+    Api(ApiError),
+    /// Something the user may want to know about, that isn't a failure
+    Notice(Notice),
+}
+
+/// Something that has happened to the lookups, shown as [Severity::Info]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notice {
+    /// The user has paused the queue
+    Paused,
+    /// The queue is paused while the browser is offline
+    Offline,
+    /// Lookups were cancelled, since their names were removed from the list
+    Cancelled,
+    /// Genders were estimated offline, until the API can be reached
+    OfflineEstimates,
+}
+
+/// Something the user can do about an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Look the affected names up again
+    Retry,
+    /// Enter an API key (for a paid plan), and then retry
+    EnterApiKey,
+}
+
+/// An error to display to the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppError {
+    pub severity: Severity,
+    /// What went wrong (or happened), shown in the user's language (see
+    /// [Messages::api_error](crate::i18n::Messages::api_error) and
+    /// [Messages::notice](crate::i18n::Messages::notice))
+    pub problem: Problem,
+    /// The API service the error came from, if any
+    pub service: Option<Service>,
+    /// The names whose lookups were affected, if any
+    pub names: Vec<Name>,
+    pub actions: Vec<Action>,
+}
+
+impl AppError {
+    /// Error from an API request for the given names
+    pub fn api(err: &ApiError, service: Service, names: Vec<Name>) -> Self {
+        AppError {
+            severity: api_severity(err),
+            problem: Problem::Api(err.clone()),
+            service: Some(service),
+            names,
+            actions: api_actions(err),
+        }
+    }
+
+    /// Error from a lookup for the page for a single name. There's no retrying
+    /// these from the message, since the page looks the name up afresh each
+    /// time it's opened.
+    pub fn detail(err: &ApiError, names: Vec<Name>) -> Self {
+        AppError {
+            severity: api_severity(err),
+            problem: Problem::Api(err.clone()),
+            service: None,
+            names,
+            actions: api_actions(err)
+                .into_iter()
+                .filter(|&a| a != Action::Retry)
+                .collect(),
+        }
+    }
+
+    /// Notice about the lookups, for the given service and names if any.
+    /// There's nothing to do about these, and they go away by themselves.
+    pub fn notice(notice: Notice, service: Option<Service>, names: Vec<Name>) -> Self {
+        AppError {
+            severity: Severity::Info,
+            problem: Problem::Notice(notice),
+            service,
+            names,
+            actions: vec![],
+        }
+    }
+
+    /// Whether this is essentially the same error as another, albeit perhaps
+    /// for different names
+    pub fn is_repeat_of(&self, other: &AppError) -> bool {
+        self.severity == other.severity
+            && self.problem == other.problem
+            && self.service == other.service
+    }
+
+    /// Add more affected names, skipping any already here.
+    pub fn merge_names(&mut self, names: Vec<Name>) {
+        let known: HashSet<Name> = self.names.iter().cloned().collect();
+        self.names.extend(names.into_iter().filter(|name| !known.contains(name)));
+    }
+
+    /// Whether this won't go away until the user does something about it, so
+    /// should stay on screen until dismissed
+    pub fn needs_user_action(&self) -> bool {
+        self.actions.contains(&Action::EnterApiKey)
+    }
+}
+
+/// How serious an API error is
+fn api_severity(err: &ApiError) -> Severity {
+    match err {
+        // Most likely just a network blip
+        ApiError::Http(_) | ApiError::Timeout => Severity::Warn,
+        // Only affects one name
+        ApiError::InvalidName(_) => Severity::Warn,
+        ApiError::BadResponse(_)
+        | ApiError::InvalidApiKey(_)
+        | ApiError::SubscriptionInactive(_)
+        | ApiError::LimitExceeded
+        | ApiError::ServerError(..) => Severity::Error,
+    }
+}
+
+/// What the user can do about an API error
+fn api_actions(err: &ApiError) -> Vec<Action> {
+    match err {
+        ApiError::Http(_)
+        | ApiError::Timeout
+        | ApiError::BadResponse(_)
+        | ApiError::ServerError(..) => vec![Action::Retry],
+        ApiError::InvalidApiKey(_)
+        | ApiError::SubscriptionInactive(_)
+        | ApiError::LimitExceeded => vec![Action::EnterApiKey],
+        // Retrying won't help: the name needs fixing.
+        ApiError::InvalidName(_) => vec![],
+    }
+}
@@ -0,0 +1,190 @@
+//! German messages and country names
+
+use names_core::iso3166::{Continent, Grouping, Region, SubRegion};
+
+use super::Messages;
+use crate::api::{ApiError, Service};
+use crate::error::Notice;
+
+pub const MESSAGES: Messages = Messages {
+    title: "Wahrscheinlichkeitsanalyse von Vornamen",
+    instructions: "Vornamen in dieses Feld eingeben oder einfügen",
+    placeholder: "Vornamen...",
+    footer_services: [
+        "Dies ist eine direkte Schnittstelle zu den API-Diensten ",
+        " und ",
+        ".",
+    ],
+    footer_quota: "Diese bieten ein kostenloses Kontingent von 1000 Abfragen pro Tag \
+                   (pro IP-Adresse).",
+
+    service: |service| match service {
+        Service::Gender => "Geschlecht",
+        Service::Country => "Land",
+    },
+    progress: |resolved, pending, failed| {
+        format!("{resolved} erledigt, {pending} ausstehend, {failed} fehlgeschlagen")
+    },
+    eta: |minutes| match minutes {
+        0 => "Weniger als eine Minute verbleibend".to_owned(),
+        _ => format!("Etwa {minutes} Min. verbleibend"),
+    },
+    pause: "Pause",
+    resume: "Fortsetzen",
+    paused: "Pausiert",
+    offline: "Offline - warte auf die Verbindung",
+    queue_depth: |queued, in_flight| {
+        format!("{queued} in der Warteschlange, {in_flight} laufend")
+    },
+    backing_off: "(zu viele Anfragen - neuer Versuch in Kürze)",
+    saved_lookups: |num_names| format!("{num_names} Abfragen gespart (gelöschte Namen)"),
+
+    api_error: |err| match err {
+        ApiError::Http(details) => format!("API-Anfrage fehlgeschlagen: {details}"),
+        ApiError::BadResponse(details) => format!("Unerwartete API-Antwort ({details})"),
+        ApiError::InvalidApiKey(message) => format!(
+            "Ungültiger API-Schlüssel ({message}) - Schlüssel prüfen oder löschen, um \
+             das kostenlose Kontingent zu nutzen"
+        ),
+        ApiError::SubscriptionInactive(message) => format!(
+            "API-Abonnement ist nicht aktiv ({message}) - verlängern oder Schlüssel \
+             löschen, um das kostenlose Kontingent zu nutzen"
+        ),
+        ApiError::InvalidName(message) => format!("Ungültiger Name ({message})"),
+        ApiError::Timeout => "Zeitüberschreitung bei der API-Anfrage".to_owned(),
+        ApiError::LimitExceeded => {
+            "Tägliches API-Limit überschritten - morgen erneut versuchen".to_owned()
+        }
+        ApiError::ServerError(status, message) => {
+            format!("Server meldete Fehlercode {status} ({message})")
+        }
+    },
+    notice: |notice| match notice {
+        Notice::Paused => "Abfragen pausiert - zum Weitermachen fortsetzen",
+        Notice::Offline => "Offline - Abfragen pausiert, bis die Verbindung zurück ist",
+        Notice::Cancelled => "Abfragen für nicht mehr aufgeführte Namen abgebrochen",
+        Notice::OfflineEstimates => {
+            "Geschlechter offline geschätzt, bis die API erreichbar ist"
+        }
+    },
+    affected_names: |num_names| format!("({num_names} Namen betroffen)"),
+    retry: "Erneut versuchen",
+    api_key: "API-Schlüssel",
+    save_api_key: "Schlüssel speichern und erneut versuchen",
+
+    copy_button: "M/F-Spalte in die Zwischenablage kopieren",
+    copied: |num_rows| format!("✓ {num_rows} Zeilen in die Zwischenablage kopiert"),
+
+    error_badge: "⚠ Fehler",
+    offline_estimate: "Offline-Schätzung",
+    offline_estimate_title: "Aus öffentlichen Vornamensstatistiken geschätzt, da die \
+                             API nicht erreichbar war",
+    other: "Andere",
+    unaccounted: "Nicht zugeordnet",
+    unknown: "Unbekannt",
+    show_all_countries: "Alle Länder anzeigen",
+    across_all_names: |num_names| format!("Über alle {num_names} Namen"),
+
+    click_to_override: "Zum Überschreiben klicken",
+    manual_override: "Manuell gesetzt - zum Ändern klicken",
+    use_api_result: "API-Ergebnis",
+    cancel: "Abbrechen",
+    note_placeholder: "Notiz...",
+    full_form_used: |name, full| {
+        format!(
+            "Ergebnisse für {full}, da {name} eine Kurzform davon ist - klicken, um nur \
+             {name} zu verwenden"
+        )
+    },
+    full_form_checked: |name, full| {
+        format!(
+            "{full} wurde auch abgefragt, da {name} eine Kurzform davon ist, hat aber \
+             weniger Ergebnisse - klicken zum Beenden"
+        )
+    },
+    full_form_skipped: |name, full| {
+        format!(
+            "{name} ist vielleicht eine Kurzform von {full} - klicken, um das auch \
+             abzufragen"
+        )
+    },
+
+    variants_heading: "Schreibvarianten",
+    split_out: "Aus dieser Gruppe lösen",
+    merge_placeholder: "Zusammenführen mit...",
+    merge_title: "Einen anderen Namen aus der Liste eingeben, um seine Gruppe mit \
+                  dieser zusammenzuführen",
+
+    back_to_list: "← Zurück zur Liste",
+    detail_gender: "Geschlecht",
+    detail_countries: "Länder",
+    localised_heading: "Geschlecht nach Land",
+    add_country: "Land hinzufügen...",
+    remove_country: "Dieses Land entfernen",
+    detail_age: "Alter",
+    people: |count| format!("aus {count} Personen"),
+    interval: |low, high| {
+        format!("95-%-Konfidenzintervall {:.0}-{:.0} %", low * 100.0, high * 100.0)
+    },
+    age_estimate: |age| format!("Geschätztes Alter {age} Jahre"),
+    no_age: "Keine Altersschätzung",
+    raw_json: "Rohe JSON-Antworten",
+
+    countries_shown_inline: "Länder pro Zeile",
+    group_countries_by: "Länder gruppieren nach",
+    language: "Sprache",
+    request_timeout: "Zeitlimit für Anfragen (s)",
+    api_base_url: "Proxy-URL",
+    api_base_url_default: "Direkt zu den APIs",
+    show_notes: "Notizspalte",
+    show_variants: "Schreibvarianten",
+    offline_estimates: "Offline-Schätzungen",
+
+    country: |country| country.translated_name("de").unwrap_or(country.name),
+    grouping: |grouping| match grouping {
+        Grouping::Country => "Land",
+        Grouping::SubRegion => "Subregion",
+        Grouping::Region => "Region",
+        Grouping::Continent => "Kontinent",
+    },
+    region: |region| match region {
+        Region::Africa => "Afrika",
+        Region::Americas => "Amerika",
+        Region::Asia => "Asien",
+        Region::Europe => "Europa",
+        Region::Oceania => "Ozeanien",
+    },
+    sub_region: |sub_region| match sub_region {
+        SubRegion::NorthernAfrica => "Nordafrika",
+        SubRegion::EasternAfrica => "Ostafrika",
+        SubRegion::MiddleAfrica => "Zentralafrika",
+        SubRegion::SouthernAfrica => "Südliches Afrika",
+        SubRegion::WesternAfrica => "Westafrika",
+        SubRegion::Caribbean => "Karibik",
+        SubRegion::CentralAmerica => "Zentralamerika",
+        SubRegion::SouthAmerica => "Südamerika",
+        SubRegion::NorthernAmerica => "Nordamerika",
+        SubRegion::CentralAsia => "Zentralasien",
+        SubRegion::EasternAsia => "Ostasien",
+        SubRegion::SouthEasternAsia => "Südostasien",
+        SubRegion::SouthernAsia => "Südasien",
+        SubRegion::WesternAsia => "Westasien",
+        SubRegion::EasternEurope => "Osteuropa",
+        SubRegion::NorthernEurope => "Nordeuropa",
+        SubRegion::SouthernEurope => "Südeuropa",
+        SubRegion::WesternEurope => "Westeuropa",
+        SubRegion::AustraliaAndNewZealand => "Australien und Neuseeland",
+        SubRegion::Melanesia => "Melanesien",
+        SubRegion::Micronesia => "Mikronesien",
+        SubRegion::Polynesia => "Polynesien",
+    },
+    continent: |continent| match continent {
+        Continent::Africa => "Afrika",
+        Continent::Antarctica => "Antarktika",
+        Continent::Asia => "Asien",
+        Continent::Europe => "Europa",
+        Continent::NorthAmerica => "Nordamerika",
+        Continent::Oceania => "Ozeanien",
+        Continent::SouthAmerica => "Südamerika",
+    },
+};
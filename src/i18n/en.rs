@@ -0,0 +1,141 @@
+//! English messages. Country and region names come straight from
+//! [names_core::iso3166].
+
+use names_core::iso3166::{Continent, Grouping, Region, SubRegion};
+
+use super::Messages;
+use crate::api::{ApiError, Service};
+use crate::error::Notice;
+
+pub const MESSAGES: Messages = Messages {
+    title: "First Name Probabilistic Analysis",
+    instructions: "Enter or paste first names into this box",
+    placeholder: "First names...",
+    footer_services: ["This is a direct interface over the ", " and ", " API services."],
+    footer_quota: "These have a free quota of 1000 name queries per day (per IP \
+                   address).",
+
+    service: |service| match service {
+        Service::Gender => "Gender",
+        Service::Country => "Country",
+    },
+    progress: |resolved, pending, failed| {
+        format!("{resolved} resolved, {pending} pending, {failed} failed")
+    },
+    eta: |minutes| match minutes {
+        0 => "Less than a minute remaining".to_owned(),
+        _ => format!("About {minutes} min remaining"),
+    },
+    pause: "Pause",
+    resume: "Resume",
+    paused: "Paused",
+    offline: "Offline - waiting for the connection to return",
+    queue_depth: |queued, in_flight| format!("{queued} queued, {in_flight} in flight"),
+    backing_off: "(too many requests - retrying shortly)",
+    saved_lookups: |num_names| {
+        format!("Saved {num_names} lookups by skipping deleted names")
+    },
+
+    api_error: |err| match err {
+        ApiError::Http(details) => format!("API request failed: {details}"),
+        ApiError::BadResponse(details) => format!("Unexpected API response ({details})"),
+        ApiError::InvalidApiKey(message) => format!(
+            "Invalid API key ({message}) - check the key, or clear it to use the free \
+             quota"
+        ),
+        ApiError::SubscriptionInactive(message) => format!(
+            "API subscription is not active ({message}) - renew it, or clear the key to \
+             use the free quota"
+        ),
+        ApiError::InvalidName(message) => format!("Invalid name ({message})"),
+        ApiError::Timeout => "API request timed out".to_owned(),
+        ApiError::LimitExceeded => {
+            "Daily API limit exceeded - try again tomorrow".to_owned()
+        }
+        ApiError::ServerError(status, message) => {
+            format!("Server returned error code {status} ({message})")
+        }
+    },
+    notice: |notice| match notice {
+        Notice::Paused => "Lookups paused - resume them to carry on",
+        Notice::Offline => "Offline - lookups paused until the connection returns",
+        Notice::Cancelled => "Lookups cancelled for names no longer in the list",
+        Notice::OfflineEstimates => {
+            "Genders estimated offline until the API can be reached"
+        }
+    },
+    affected_names: |num_names| format!("({num_names} names affected)"),
+    retry: "Retry",
+    api_key: "API key",
+    save_api_key: "Save key and retry",
+
+    copy_button: "Copy M/F column to clipboard",
+    copied: |num_rows| format!("✓ Copied {num_rows} rows to clipboard"),
+
+    error_badge: "⚠ Error",
+    offline_estimate: "offline estimate",
+    offline_estimate_title: "Estimated from public name statistics, since the API was \
+                             unavailable",
+    other: "Other",
+    unaccounted: "Unaccounted",
+    unknown: "Unknown",
+    show_all_countries: "Show all countries",
+    across_all_names: |num_names| format!("Across all {num_names} names"),
+
+    click_to_override: "Click to override",
+    manual_override: "Set manually - click to change",
+    use_api_result: "API result",
+    cancel: "Cancel",
+    note_placeholder: "Note...",
+    full_form_used: |name, full| {
+        format!(
+            "Showing results for {full}, since {name} is short for it - click to use \
+             {name} alone"
+        )
+    },
+    full_form_checked: |name, full| {
+        format!(
+            "Also looked up {full}, since {name} is short for it, but it has fewer \
+             results - click to stop"
+        )
+    },
+    full_form_skipped: |name, full| {
+        format!("{name} may be short for {full} - click to look that up too")
+    },
+
+    variants_heading: "Spelling variants",
+    split_out: "Split out of this group",
+    merge_placeholder: "Merge with...",
+    merge_title: "Enter another name from the list, to merge its group into this one",
+
+    back_to_list: "← Back to the list",
+    detail_gender: "Gender",
+    detail_countries: "Countries",
+    localised_heading: "Gender by country",
+    add_country: "Add a country...",
+    remove_country: "Remove this country",
+    detail_age: "Age",
+    people: |count| format!("based on {count} people"),
+    interval: |low, high| {
+        format!("95% confidence interval {:.0}-{:.0}%", low * 100.0, high * 100.0)
+    },
+    age_estimate: |age| format!("Estimated age {age}"),
+    no_age: "No age estimate",
+    raw_json: "Raw JSON responses",
+
+    countries_shown_inline: "Countries shown inline",
+    group_countries_by: "Group countries by",
+    language: "Language",
+    request_timeout: "Request timeout (s)",
+    api_base_url: "Proxy URL",
+    api_base_url_default: "Direct to the APIs",
+    show_notes: "Notes column",
+    show_variants: "Spelling variants",
+    offline_estimates: "Offline estimates",
+
+    country: |country| country.name,
+    grouping: Grouping::name,
+    region: Region::name,
+    sub_region: SubRegion::name,
+    continent: Continent::name,
+};
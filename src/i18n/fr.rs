@@ -0,0 +1,198 @@
+//! French messages and country names
+
+use names_core::iso3166::{Continent, Grouping, Region, SubRegion};
+
+use super::Messages;
+use crate::api::{ApiError, Service};
+use crate::error::Notice;
+
+pub const MESSAGES: Messages = Messages {
+    title: "Analyse probabiliste des prénoms",
+    instructions: "Saisissez ou collez des prénoms dans cette zone",
+    placeholder: "Prénoms...",
+    footer_services: [
+        "Ceci est une interface directe vers les services d'API ",
+        " et ",
+        ".",
+    ],
+    footer_quota: "Ceux-ci offrent un quota gratuit de 1000 requêtes par jour (par \
+                   adresse IP).",
+
+    service: |service| match service {
+        Service::Gender => "Genre",
+        Service::Country => "Pays",
+    },
+    progress: |resolved, pending, failed| {
+        format!("{resolved} résolus, {pending} en attente, {failed} en échec")
+    },
+    eta: |minutes| match minutes {
+        0 => "Moins d'une minute restante".to_owned(),
+        _ => format!("Environ {minutes} min restantes"),
+    },
+    pause: "Pause",
+    resume: "Reprendre",
+    paused: "En pause",
+    offline: "Hors ligne - en attente du retour de la connexion",
+    queue_depth: |queued, in_flight| format!("{queued} en attente, {in_flight} en cours"),
+    backing_off: "(trop de requêtes - nouvel essai sous peu)",
+    saved_lookups: |num_names| {
+        format!("{num_names} requêtes économisées (prénoms supprimés)")
+    },
+
+    api_error: |err| match err {
+        ApiError::Http(details) => format!("Échec de la requête à l'API : {details}"),
+        ApiError::BadResponse(details) => {
+            format!("Réponse inattendue de l'API ({details})")
+        }
+        ApiError::InvalidApiKey(message) => format!(
+            "Clé d'API invalide ({message}) - vérifiez la clé, ou effacez-la pour \
+             utiliser le quota gratuit"
+        ),
+        ApiError::SubscriptionInactive(message) => format!(
+            "L'abonnement à l'API n'est pas actif ({message}) - renouvelez-le, ou \
+             effacez la clé pour utiliser le quota gratuit"
+        ),
+        ApiError::InvalidName(message) => format!("Prénom invalide ({message})"),
+        ApiError::Timeout => "La requête à l'API a expiré".to_owned(),
+        ApiError::LimitExceeded => {
+            "Limite quotidienne de l'API atteinte - réessayez demain".to_owned()
+        }
+        ApiError::ServerError(status, message) => {
+            format!("Le serveur a renvoyé le code d'erreur {status} ({message})")
+        }
+    },
+    notice: |notice| match notice {
+        Notice::Paused => "Recherches en pause - reprenez-les pour continuer",
+        Notice::Offline => {
+            "Hors ligne - recherches en pause jusqu'au retour de la connexion"
+        }
+        Notice::Cancelled => "Recherches annulées pour les prénoms retirés de la liste",
+        Notice::OfflineEstimates => {
+            "Genres estimés hors ligne en attendant de joindre l'API"
+        }
+    },
+    affected_names: |num_names| format!("({num_names} prénoms concernés)"),
+    retry: "Réessayer",
+    api_key: "Clé d'API",
+    save_api_key: "Enregistrer la clé et réessayer",
+
+    copy_button: "Copier la colonne M/F dans le presse-papiers",
+    copied: |num_rows| format!("✓ {num_rows} lignes copiées dans le presse-papiers"),
+
+    error_badge: "⚠ Erreur",
+    offline_estimate: "estimation hors ligne",
+    offline_estimate_title: "Estimé à partir de statistiques publiques sur les prénoms, \
+                             l'API n'étant pas disponible",
+    other: "Autres",
+    unaccounted: "Non attribué",
+    unknown: "Inconnu",
+    show_all_countries: "Afficher tous les pays",
+    across_all_names: |num_names| format!("Sur l'ensemble des {num_names} prénoms"),
+
+    click_to_override: "Cliquez pour corriger",
+    manual_override: "Défini manuellement - cliquez pour modifier",
+    use_api_result: "Résultat de l'API",
+    cancel: "Annuler",
+    note_placeholder: "Note...",
+    full_form_used: |name, full| {
+        format!(
+            "Résultats pour {full}, dont {name} est un diminutif - cliquez pour \
+             n'utiliser que {name}"
+        )
+    },
+    full_form_checked: |name, full| {
+        format!(
+            "{full} a aussi été recherché, {name} en étant un diminutif, mais a moins \
+             de résultats - cliquez pour arrêter"
+        )
+    },
+    full_form_skipped: |name, full| {
+        format!(
+            "{name} est peut-être un diminutif de {full} - cliquez pour le rechercher \
+             aussi"
+        )
+    },
+
+    variants_heading: "Variantes orthographiques",
+    split_out: "Retirer de ce groupe",
+    merge_placeholder: "Fusionner avec...",
+    merge_title: "Saisissez un autre prénom de la liste, pour fusionner son groupe avec \
+                  celui-ci",
+
+    back_to_list: "← Retour à la liste",
+    detail_gender: "Genre",
+    detail_countries: "Pays",
+    localised_heading: "Genre par pays",
+    add_country: "Ajouter un pays...",
+    remove_country: "Retirer ce pays",
+    detail_age: "Âge",
+    people: |count| format!("sur {count} personnes"),
+    interval: |low, high| {
+        format!(
+            "intervalle de confiance à 95 % : {:.0}-{:.0} %",
+            low * 100.0,
+            high * 100.0
+        )
+    },
+    age_estimate: |age| format!("Âge estimé : {age} ans"),
+    no_age: "Pas d'estimation d'âge",
+    raw_json: "Réponses JSON brutes",
+
+    countries_shown_inline: "Pays affichés par ligne",
+    group_countries_by: "Regrouper les pays par",
+    language: "Langue",
+    request_timeout: "Délai des requêtes (s)",
+    api_base_url: "URL du proxy",
+    api_base_url_default: "Accès direct aux API",
+    show_notes: "Colonne de notes",
+    show_variants: "Variantes orthographiques",
+    offline_estimates: "Estimations hors ligne",
+
+    country: |country| country.translated_name("fr").unwrap_or(country.name),
+    grouping: |grouping| match grouping {
+        Grouping::Country => "Pays",
+        Grouping::SubRegion => "Sous-région",
+        Grouping::Region => "Région",
+        Grouping::Continent => "Continent",
+    },
+    region: |region| match region {
+        Region::Africa => "Afrique",
+        Region::Americas => "Amériques",
+        Region::Asia => "Asie",
+        Region::Europe => "Europe",
+        Region::Oceania => "Océanie",
+    },
+    sub_region: |sub_region| match sub_region {
+        SubRegion::NorthernAfrica => "Afrique du Nord",
+        SubRegion::EasternAfrica => "Afrique de l'Est",
+        SubRegion::MiddleAfrica => "Afrique centrale",
+        SubRegion::SouthernAfrica => "Afrique australe",
+        SubRegion::WesternAfrica => "Afrique de l'Ouest",
+        SubRegion::Caribbean => "Caraïbes",
+        SubRegion::CentralAmerica => "Amérique centrale",
+        SubRegion::SouthAmerica => "Amérique du Sud",
+        SubRegion::NorthernAmerica => "Amérique septentrionale",
+        SubRegion::CentralAsia => "Asie centrale",
+        SubRegion::EasternAsia => "Asie de l'Est",
+        SubRegion::SouthEasternAsia => "Asie du Sud-Est",
+        SubRegion::SouthernAsia => "Asie du Sud",
+        SubRegion::WesternAsia => "Asie de l'Ouest",
+        SubRegion::EasternEurope => "Europe de l'Est",
+        SubRegion::NorthernEurope => "Europe du Nord",
+        SubRegion::SouthernEurope => "Europe du Sud",
+        SubRegion::WesternEurope => "Europe de l'Ouest",
+        SubRegion::AustraliaAndNewZealand => "Australie et Nouvelle-Zélande",
+        SubRegion::Melanesia => "Mélanésie",
+        SubRegion::Micronesia => "Micronésie",
+        SubRegion::Polynesia => "Polynésie",
+    },
+    continent: |continent| match continent {
+        Continent::Africa => "Afrique",
+        Continent::Antarctica => "Antarctique",
+        Continent::Asia => "Asie",
+        Continent::Europe => "Europe",
+        Continent::NorthAmerica => "Amérique du Nord",
+        Continent::Oceania => "Océanie",
+        Continent::SouthAmerica => "Amérique du Sud",
+    },
+};
@@ -0,0 +1,189 @@
+//! Localisation: a message catalogue for the UI chrome, plus country and region
+//! names, for each supported locale.
+
+use names_core::iso3166::{Continent, Country, Grouping, Labels, Region, SubRegion};
+
+use crate::api::{ApiError, Service};
+use crate::error::Notice;
+use crate::prelude::window;
+
+mod de;
+mod en;
+mod fr;
+
+/// Supported UI locales
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Fr,
+    De,
+}
+
+impl Locale {
+    /// All supported locales, in the order offered to the user
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Fr, Locale::De];
+
+    /// Language tag, eg "fr"
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+            Locale::De => "de",
+        }
+    }
+
+    /// Name of the language, in that language (so users can always find their
+    /// own, whatever the current locale).
+    pub fn endonym(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Fr => "Français",
+            Locale::De => "Deutsch",
+        }
+    }
+
+    /// Message catalogue for this locale
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Locale::En => &en::MESSAGES,
+            Locale::Fr => &fr::MESSAGES,
+            Locale::De => &de::MESSAGES,
+        }
+    }
+
+    /// Find a locale from a language tag such as "de-CH", ignoring any region.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        Locale::ALL.into_iter().find(|l| l.tag().eq_ignore_ascii_case(language))
+    }
+
+    /// Best match for the browser's preferred language, defaulting to English.
+    pub fn from_browser() -> Locale {
+        window()
+            .and_then(|window| window.navigator().language())
+            .and_then(|tag| Locale::from_tag(&tag))
+            .unwrap_or(Locale::En)
+    }
+}
+
+/// Message catalogue: every user-visible string, for one locale. Strings with
+/// parameters are functions, so translations can reorder things as needed.
+pub struct Messages {
+    // Main page
+    pub title: &'static str,
+    pub instructions: &'static str,
+    pub placeholder: &'static str,
+    /// Footer sentence wrapped around the two API service links:
+    /// before, between, after
+    pub footer_services: [&'static str; 3],
+    pub footer_quota: &'static str,
+
+    // Request queue
+    pub service: fn(Service) -> &'static str,
+    /// Names resolved, pending and failed
+    pub progress: fn(usize, usize, usize) -> String,
+    /// Estimated minutes remaining
+    pub eta: fn(u32) -> String,
+    pub pause: &'static str,
+    pub resume: &'static str,
+    pub paused: &'static str,
+    pub offline: &'static str,
+    /// Names queued, and names in flight
+    pub queue_depth: fn(usize, usize) -> String,
+    pub backing_off: &'static str,
+    pub saved_lookups: fn(usize) -> String,
+
+    // Errors
+    pub api_error: fn(&ApiError) -> String,
+    pub notice: fn(Notice) -> &'static str,
+    pub affected_names: fn(usize) -> String,
+    pub retry: &'static str,
+    pub api_key: &'static str,
+    pub save_api_key: &'static str,
+
+    // Copy button
+    pub copy_button: &'static str,
+    pub copied: fn(u16) -> String,
+
+    // Results table
+    pub error_badge: &'static str,
+    /// Badge on gender results estimated offline, and its tooltip
+    pub offline_estimate: &'static str,
+    pub offline_estimate_title: &'static str,
+    pub other: &'static str,
+    pub unaccounted: &'static str,
+    pub unknown: &'static str,
+    pub show_all_countries: &'static str,
+    pub across_all_names: fn(usize) -> String,
+
+    // Overrides and notes
+    pub click_to_override: &'static str,
+    pub manual_override: &'static str,
+    pub use_api_result: &'static str,
+    pub cancel: &'static str,
+    pub note_placeholder: &'static str,
+    /// Tooltips on a nickname's full form, given the nickname and full form:
+    /// when the full form's results are shown, when the nickname's own are
+    /// shown instead, and when the user has opted out of the full form
+    pub full_form_used: fn(&str, &str) -> String,
+    pub full_form_checked: fn(&str, &str) -> String,
+    pub full_form_skipped: fn(&str, &str) -> String,
+
+    // Spelling variants
+    pub variants_heading: &'static str,
+    pub split_out: &'static str,
+    pub merge_placeholder: &'static str,
+    pub merge_title: &'static str,
+
+    // Page for a single name
+    pub back_to_list: &'static str,
+    pub detail_gender: &'static str,
+    pub detail_countries: &'static str,
+    pub localised_heading: &'static str,
+    pub add_country: &'static str,
+    pub remove_country: &'static str,
+    pub detail_age: &'static str,
+    /// Number of people a result is based on
+    pub people: fn(u32) -> String,
+    /// 95% confidence interval for a probability, from low to high
+    pub interval: fn(f32, f32) -> String,
+    pub age_estimate: fn(u32) -> String,
+    pub no_age: &'static str,
+    pub raw_json: &'static str,
+
+    // Settings
+    pub countries_shown_inline: &'static str,
+    pub group_countries_by: &'static str,
+    pub language: &'static str,
+    pub request_timeout: &'static str,
+    pub api_base_url: &'static str,
+    /// Placeholder when going straight to the APIs
+    pub api_base_url_default: &'static str,
+    pub show_notes: &'static str,
+    pub show_variants: &'static str,
+    pub offline_estimates: &'static str,
+
+    // Country and region names
+    pub country: fn(&Country) -> &'static str,
+    pub grouping: fn(Grouping) -> &'static str,
+    pub region: fn(Region) -> &'static str,
+    pub sub_region: fn(SubRegion) -> &'static str,
+    pub continent: fn(Continent) -> &'static str,
+}
+
+/// Country and region names in the UI locale, for grouping country results
+impl Labels for Messages {
+    fn country(&self, country: &Country) -> &'static str { (self.country)(country) }
+
+    fn sub_region(&self, sub_region: SubRegion) -> &'static str {
+        (self.sub_region)(sub_region)
+    }
+
+    fn region(&self, region: Region) -> &'static str { (self.region)(region) }
+
+    fn continent(&self, continent: Continent) -> &'static str {
+        (self.continent)(continent)
+    }
+
+    fn unknown(&self) -> &'static str { self.unknown }
+}
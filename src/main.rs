@@ -7,20 +7,78 @@ static ALLOC: wee_alloc::WeeAlloc<'_> = wee_alloc::WeeAlloc::INIT;
 mod api;
 mod component;
 mod db;
-mod iso3166;
+mod error;
+mod i18n;
+mod nicknames;
+mod offline;
+mod overrides;
 mod prelude;
+mod route;
+mod settings;
 
+use std::rc::Rc;
+
+use names_core::cluster::Edit;
 use prelude::*;
+use route::Route;
 
 /// Main app component
 #[tracing::instrument(skip_all)]
 fn app(cx: Scope<'_>) -> Element<'_> {
-    // The [Names] entered by the user
+    // The [Names] entered by the user, and the snapshot of them committed for
+    // querying (lagging slightly behind while the user is still typing)
     let names = use_state(&cx, Names::default);
+    let committed = use_state(&cx, Names::default);
+
+    // The user's own [Overrides] of API results
+    let overrides = use_ref(&cx, overrides::Overrides::load);
+
+    // The names to look up: those committed, plus the full forms of any
+    // nicknames among them
+    let previous = cx.use_hook(|_| Rc::<Names>::default());
+    let lookups = nicknames::lookups(&committed.current(), &overrides.read(), previous);
+    *previous = lookups.clone();
 
-    // The [Db] contained all pending and cached API results
+    // The [Db] contained all pending and cached API results, and the
+    // [db::Queue] tracks the requests still in progress
     let db = use_ref(&cx, Db::new);
-    db::start_any_requests(names, db.clone());
+    let queue = use_ref(&cx, db::Queue::default);
+    queue.write_silent().set_timeout(*use_read(&cx, settings::REQUEST_TIMEOUT));
+    cx.use_hook(|_| db::watch_connectivity(db, queue));
+    offline::set_enabled(*use_read(&cx, settings::OFFLINE_ESTIMATES));
+    cx.use_hook(|_| offline::preload());
+    db::start_any_requests(lookups, db.clone(), queue.clone());
+
+    // The groups of spelling variants, if shown, and the user's edits to them
+    let show_variants = *use_read(&cx, settings::SHOW_VARIANTS);
+    let variant_edits = use_state(&cx, Vec::<Edit>::new);
+    let variants = show_variants.then(|| {
+        rsx! {
+            Clusters { names: names.clone(), db: db.clone(), edits: variant_edits.clone() }
+        }
+    });
+
+    // The page showing: the main page stays mounted underneath the page for a
+    // single name, so the names entered are still there on the way back.
+    let page = use_read(&cx, route::ROUTE);
+    let set_route = use_set(&cx, route::ROUTE);
+    cx.use_hook(|_| route::watch_history(set_route));
+    let (detail, main_class) = match page {
+        Route::Name(name) => {
+            let detail = rsx! {
+                Detail { key: "{name}", name: name.clone(), db: db.clone(), queue: queue.clone() }
+            };
+            (Some(detail), "d-none")
+        }
+        Route::Main => (None, ""),
+    };
+
+    // Messages for the current locale
+    let msgs = use_read(&cx, settings::LOCALE).messages();
+    let [before, between, after] = msgs.footer_services;
+    let title = msgs.title;
+    let instructions = msgs.instructions;
+    let footer_quota = msgs.footer_quota;
 
     // Top-level view
     cx.render(rsx! {
@@ -31,30 +89,43 @@ fn app(cx: Scope<'_>) -> Element<'_> {
                 img { src: "/badge.png", alt: "logo" }
                 div {
                     class: "navbar-brand mb-0 h1",
-                    "First Name Probabilistic Analysis"
+                    "{title}"
                 }
             }
-            Errors {}
-            CopyButton { names: names.clone(), db: db.clone() }
-            h4 {
-                span { class: "arrow", "⤹" }
-                "Enter or paste first names into this box"
-            }
-            main {
-                Entry { names: names.clone() }
-                Table { names: names.clone(), db: db.clone() }
+            Errors { db: db.clone(), queue: queue.clone() }
+            detail
+            div {
+                class: "{main_class}",
+                QueueStatus { names: committed.clone(), db: db.clone(), queue: queue.clone() }
+                CopyButton { names: names.clone(), db: db.clone(), overrides: overrides.clone() }
+                Settings {}
+                Summary { names: names.clone(), db: db.clone(), overrides: overrides.clone() }
+                variants
+                h4 {
+                    span { class: "arrow", "⤹" }
+                    "{instructions}"
+                }
+                main {
+                    Entry { names: names.clone(), committed: committed.clone() }
+                    Table {
+                        names: names.clone(),
+                        db: db.clone(),
+                        queue: queue.clone(),
+                        overrides: overrides.clone()
+                    }
+                }
             }
         }
         footer {
             p {
-                "This is a direct interface over the "
+                "{before}"
                 a { href: "https://genderize.io", "genderize.io" }
-                " and "
+                "{between}"
                 a { href: "https://nationalize.io", "nationalize.io" }
-                " API services."
+                "{after}"
             }
             p {
-                "These have a free quota of 1000 name queries per day (per IP address)."
+                "{footer_quota}"
             }
         }
     })
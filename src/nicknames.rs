@@ -0,0 +1,64 @@
+//! Nicknames (see [names_core::nicknames]): looking up their full forms along
+//! with them, and showing the full form's results where the nickname's own
+//! have too little data behind them. The user can opt out of this for any name.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use names_core::nicknames::{full_form, prefer_full_form};
+
+use crate::overrides::Overrides;
+use crate::prelude::*;
+
+/// The full form to look up along with a name, if it's a nickname, and the
+/// user hasn't opted out for it.
+pub fn full_form_for(name: &str, overrides: &Overrides) -> Option<&'static str> {
+    let skip = matches!(overrides.get(name), Some(o) if o.skip_full_form);
+    full_form(name).filter(|_| !skip)
+}
+
+/// Every name to look up: those entered, followed by the full forms of any
+/// nicknames among them. If that's the same as `previous`, then `previous`
+/// itself is returned, so the [Queue](crate::db::Queue) can tell nothing has
+/// changed.
+pub fn lookups(
+    names: &Rc<Names>,
+    overrides: &Overrides,
+    previous: &Rc<Names>,
+) -> Rc<Names> {
+    let mut seen: HashSet<&str> = names.iter().map(Name::as_str).collect();
+    let full_forms: Vec<_> = names
+        .iter()
+        .filter_map(|name| full_form_for(name, overrides))
+        .filter(|full| seen.insert(*full))
+        .map(Name::from_static)
+        .collect();
+    if full_forms.is_empty() {
+        return names.clone();
+    }
+    let lookups: Names = names.iter().cloned().chain(full_forms).collect();
+    match **previous == lookups {
+        true => previous.clone(),
+        false => Rc::new(lookups),
+    }
+}
+
+/// The results to show for a name, if it has been looked up yet, along with
+/// the full form they are for, if they are the full form's rather than the
+/// name's own.
+pub fn results<'a>(
+    db: &'a Db,
+    overrides: &Overrides,
+    name: &str,
+) -> (Option<&'a AllResults>, Option<&'static str>) {
+    let own = db.get(name);
+    if let (Some(own), Some(full)) = (own, full_form_for(name, overrides)) {
+        match db.get(full) {
+            Some(results) if prefer_full_form(&own.gender, &results.gender) => {
+                return (Some(results), Some(full));
+            }
+            _ => {}
+        }
+    }
+    (own, None)
+}
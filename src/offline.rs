@@ -0,0 +1,135 @@
+//! Offline estimates of gender, from the optional table of common names (see
+//! [names_core::offline]), for when the API is out of quota or out of reach.
+//! The table is a separate download from the app itself, fetched in the
+//! background shortly after start-up, so it's to hand if the connection drops.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use names_core::offline::Table;
+use reqwasm::http::Request;
+
+use crate::prelude::*;
+
+/// Where the table is served from, if it has been built (by
+/// `names offline-table`)
+const TABLE_URL: &str = "/offline/names.bin";
+
+/// Number of milliseconds after start-up before fetching the table, so it
+/// doesn't hold up the app's own first requests.
+const PRELOAD_DELAY: u32 = 5_000;
+
+/// The table being fetched, or [None] if there isn't one
+type Fetch = Shared<LocalBoxFuture<'static, Option<Rc<Table>>>>;
+
+thread_local! {
+    /// Whether offline estimates are wanted, as per the setting
+    static ENABLED: Cell<bool> = Cell::new(true);
+    /// The table, once fetching has started
+    static TABLE: RefCell<Option<Fetch>> = RefCell::new(None);
+}
+
+/// Turn offline estimates on or off.
+pub fn set_enabled(enabled: bool) { ENABLED.with(|cell| cell.set(enabled)); }
+
+/// Fetch the table in the background, if offline estimates are wanted.
+pub fn preload() {
+    spawn_local(async {
+        TimeoutFuture::new(PRELOAD_DELAY).await;
+        if ENABLED.with(Cell::get) {
+            table().await;
+        }
+    });
+}
+
+/// Fill in offline estimates for any of these names whose gender lookups are
+/// still pending or have failed, and let the user know. Pending names stay
+/// queued, so any results from the API replace the estimates once it can be
+/// reached again.
+pub fn estimate(db: &UseRef<Db>, names: Vec<Name>) {
+    if !ENABLED.with(Cell::get) || names.is_empty() {
+        return;
+    }
+    to_owned![db];
+    spawn_local(async move {
+        let table = match table().await {
+            Some(table) => table,
+            None => return,
+        };
+        let estimates: Vec<_> = {
+            let db = db.read();
+            names
+                .into_iter()
+                .filter(|name| {
+                    let gender = db.get(name).map(|results| &results.gender);
+                    matches!(gender, Some(Remote::Loading | Remote::Error))
+                })
+                .filter_map(|name| Some((table.lookup(&name)?, name)))
+                .collect()
+        };
+        if !estimates.is_empty() {
+            let mut estimated = Vec::new();
+            {
+                let mut db = db.write();
+                for (estimate, name) in estimates {
+                    if let Some(results) = db.get_mut(&name) {
+                        results.gender = Remote::Success(estimate);
+                        estimated.push(name);
+                    }
+                }
+            }
+            add_error(AppError::notice(
+                Notice::OfflineEstimates,
+                Some(Service::Gender),
+                estimated,
+            ));
+        }
+    });
+}
+
+/// Internal helper function: the table, fetching it first time through. A
+/// failed fetch is tried again next time, in case the connection was down.
+async fn table() -> Option<Rc<Table>> {
+    let fetch = TABLE.with(|table| {
+        table.borrow_mut().get_or_insert_with(|| fetch().boxed_local().shared()).clone()
+    });
+    let table = fetch.await;
+    if table.is_none() {
+        TABLE.with(|table| table.borrow_mut().take());
+    }
+    table
+}
+
+/// Internal helper function: fetch and decode the table.
+async fn fetch() -> Option<Rc<Table>> {
+    let response = match Request::get(TABLE_URL).send().await {
+        Ok(response) if response.ok() => response,
+        Ok(response) => {
+            tracing::info!("No offline names table ({})", response.status());
+            return None;
+        }
+        Err(err) => {
+            tracing::warn!("Unable to fetch offline names table: {}", err);
+            return None;
+        }
+    };
+    let buffer = match response.as_raw().array_buffer() {
+        Ok(promise) => JsFuture::from(promise).await,
+        Err(err) => Err(err),
+    };
+    let bytes = match buffer {
+        Ok(buffer) => js_sys::Uint8Array::new(&buffer).to_vec(),
+        Err(err) => {
+            tracing::warn!("Unable to fetch offline names table: {:?}", err);
+            return None;
+        }
+    };
+    match Table::decode(&bytes) {
+        Ok(table) => Some(Rc::new(table)),
+        Err(err) => {
+            tracing::error!("Unable to load offline names table: {}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! The user's own overrides and notes for individual names, which take
+//! precedence over the API results, and persist in browser local storage. This
+//! also records where the user has opted out of looking up a nickname's full
+//! form.
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// Local storage key
+const STORAGE_KEY: &str = "overrides";
+
+/// What the user has said about one name.
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Override {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gender: Option<Gender>,
+    /// ISO 3166 alpha-2 code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    /// Don't look up the full form of the name, if it's a nickname
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_full_form: bool,
+}
+
+impl Override {
+    /// Whether there is anything here worth keeping
+    fn is_empty(&self) -> bool {
+        self.gender.is_none()
+            && self.country.is_none()
+            && self.note.is_empty()
+            && !self.skip_full_form
+    }
+}
+
+/// All the user's overrides, by name.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Overrides(HashMap<Name, Override>);
+
+impl Overrides {
+    /// Load from local storage, starting afresh if there is nothing usable
+    /// there.
+    pub fn load() -> Self { LocalStorage::get(STORAGE_KEY).unwrap_or_default() }
+
+    /// The override for one name, if any
+    pub fn get(&self, name: &str) -> Option<&Override> { self.0.get(name) }
+
+    /// Update the override for one name, and persist the result.
+    pub fn update(&mut self, name: &Name, update: impl FnOnce(&mut Override)) {
+        self.edit(name, update);
+        self.save();
+    }
+
+    /// Update the override for one name, without persisting it yet (while the
+    /// user is still typing a note, say).
+    pub fn edit(&mut self, name: &Name, update: impl FnOnce(&mut Override)) {
+        let mut entry = self.0.get(name).cloned().unwrap_or_default();
+        update(&mut entry);
+        if entry.is_empty() {
+            self.0.remove(name);
+        } else {
+            self.0.insert(name.clone(), entry);
+        }
+    }
+
+    /// Persist all the overrides to local storage.
+    pub fn save(&self) {
+        if let Err(err) = LocalStorage::set(STORAGE_KEY, self) {
+            tracing::error!("Unable to save overrides: {:?}", err);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Which page is showing: the main page, or the page for a single name at
+//! `/name/<name>`. This is held as a fermi atom, and kept in step with the
+//! browser's address bar and history.
+
+use std::rc::Rc;
+
+use gloo::events::EventListener;
+use names_core::normalise::normalise_name;
+use wasm_bindgen::JsValue;
+
+use crate::prelude::*;
+
+/// Path prefix for the page for a single name
+const NAME_PREFIX: &str = "/name/";
+
+/// A page of the app
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    Main,
+    Name(Name),
+}
+
+impl Route {
+    /// The page for a path, falling back to the main page for anything
+    /// unrecognised (or a name that doesn't normalise to anything).
+    pub fn from_path(path: &str) -> Route {
+        path.strip_prefix(NAME_PREFIX)
+            .and_then(|name| urlencoding::decode(name).ok())
+            .and_then(|name| normalise_name(&name))
+            .map_or(Route::Main, Route::Name)
+    }
+
+    /// The page the browser is currently showing
+    pub fn current() -> Route {
+        window()
+            .and_then(|window| window.location().pathname().ok())
+            .map_or(Route::Main, |path| Route::from_path(&path))
+    }
+
+    /// Path for this page
+    pub fn path(&self) -> String {
+        match self {
+            Route::Main => String::from("/"),
+            Route::Name(name) => {
+                format!("{NAME_PREFIX}{}", urlencoding::encode(name))
+            }
+        }
+    }
+}
+
+/// The current page, initially whatever the browser was pointed at.
+pub static ROUTE: Atom<Route> = |_| Route::current();
+
+/// Go to another page, adding it to the browser history.
+pub fn navigate(set_route: &Rc<dyn Fn(Route)>, route: Route) {
+    let history = window().map(|window| window.history());
+    if let Some(Ok(history)) = history {
+        let path = route.path();
+        if let Err(err) = history.push_state_with_url(&JsValue::NULL, "", Some(&path)) {
+            tracing::error!("Unable to update history: {:?}", err);
+        }
+    }
+    set_route(route);
+}
+
+/// Follow the browser's back and forward buttons, for as long as the returned
+/// listener is kept.
+pub fn watch_history(set_route: &Rc<dyn Fn(Route)>) -> EventListener {
+    let window = web_sys::window().unwrap();
+    let set_route = set_route.clone();
+    EventListener::new(&window, "popstate", move |_| set_route(Route::current()))
+}
@@ -0,0 +1,38 @@
+//! Global user-adjustable settings, held as fermi atoms so any component can
+//! read them without threading props through the whole tree.
+
+use std::ops::RangeInclusive;
+
+use names_core::iso3166::Grouping;
+
+use crate::i18n::Locale;
+use crate::prelude::*;
+
+/// Maximum number of countries shown inline in each table row, before the rest
+/// are folded into an "other" bucket.
+pub static INLINE_COUNTRIES: Atom<usize> = |_| 3;
+
+/// Upper bound for [INLINE_COUNTRIES] offered in the UI.
+pub const MAX_INLINE_COUNTRIES: usize = 10;
+
+/// Granularity at which country results are presented.
+pub static GROUPING: Atom<Grouping> = |_| Grouping::Country;
+
+/// Number of seconds to wait for an API request before giving up on it.
+pub static REQUEST_TIMEOUT: Atom<u32> = |_| 30;
+
+/// Range for [REQUEST_TIMEOUT] offered in the UI.
+pub const REQUEST_TIMEOUT_RANGE: RangeInclusive<u32> = 5..=120;
+
+/// Whether to show the free-text notes column.
+pub static SHOW_NOTES: Atom<bool> = |_| false;
+
+/// Whether to show groups of spelling variants among the names.
+pub static SHOW_VARIANTS: Atom<bool> = |_| false;
+
+/// Whether to fall back on offline estimates of gender when the API is
+/// unavailable.
+pub static OFFLINE_ESTIMATES: Atom<bool> = |_| true;
+
+/// UI locale, initially the best match for the browser's language.
+pub static LOCALE: Atom<Locale> = |_| Locale::from_browser();